@@ -1,8 +1,15 @@
 use crate::{
     loaders::{LoadStatus, Loader},
+    reference::Tracker,
     sources::Source,
+    AssetRef, BoxError, Dependency, Error,
+};
+use futures::channel::oneshot::Sender;
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Instant, SystemTime},
 };
-use std::{path::PathBuf, sync::Arc};
 
 /// Any struct implementing the `Asset` trait can be Stored inside a corresponding `Manager`
 pub trait Asset<L>
@@ -16,11 +23,29 @@ where
         data_load: <L::Source as Source>::Output,
         data_ass: &Self::AssetSupplement,
         data_mgr: &Self::ManagerSupplement,
-    ) -> Result<Self::Structure, std::io::Error>;
+    ) -> Result<Self::Structure, BoxError>;
+    /// Returns the Assets the loaded data depends on, e.g. the textures of a material,
+    /// together with the TransferSupplement to load each of them with.
+    ///
+    /// The Managers holding the dependencies load them during their `maintain` and
+    /// `construct` is called once every dependency is loaded. If a dependency fails the Asset fails with
+    /// `Error::DependencyFailed`, if a dependency is reloaded the Asset is reloaded as well.
+    /// By default an Asset has no dependencies.
+    fn dependencies(
+        _data_load: &<L::Source as Source>::Output,
+        _data_ass: &Self::AssetSupplement,
+        _data_mgr: &Self::ManagerSupplement,
+    ) -> Vec<(Dependency, L::TransferSupplement)> {
+        Vec::new()
+    }
+    /// Returns the size of a constructed Asset in bytes, used by `Manager::cache`.
+    /// By default Assets have a size of 0.
+    fn size(_asset: &Self::Structure) -> usize {
+        0
+    }
 }
 
 /// `AssetHandle` holds the Asset and its Metadata
-#[derive(Clone)]
 pub(crate) struct AssetHandle<A, L>
 where
     A: Asset<L>,
@@ -28,8 +53,32 @@ where
 {
     pub(crate) path: PathBuf,
     asset: Option<Arc<A::Structure>>,
+    tracker: Option<Arc<Tracker>>,
+    error: Option<Error>,
+    waiting: Vec<Sender<Result<AssetRef<A::Structure>, Error>>>,
     pub status: LoadStatus,
     pub data: A::AssetSupplement,
+    /// Modification time of the file when it was last requested. Only tracked by watching Managers.
+    pub(crate) modified: Option<SystemTime>,
+    /// The TransferSupplement of the last load, used to reload the Asset.
+    pub(crate) supp: Option<L::TransferSupplement>,
+    /// A reload has been requested while the Asset stays loaded.
+    pub(crate) reloading: bool,
+    /// Incremented every time an Asset is stored in the handle.
+    pub(crate) version: u64,
+    /// The `Asset::size` of the stored Asset.
+    pub(crate) size: usize,
+    /// When the Asset was last queued for unloading after a grace period, as time and `maintain` count.
+    pub(crate) unreferenced_since: Option<(Instant, u64)>,
+    /// Pinned Assets are never unloaded or dropped by `maintain`.
+    pub(crate) pinned: bool,
+    /// Returned by `Manager::get_or_fallback` while the Asset is not loaded.
+    pub(crate) fallback: Option<Arc<A::Structure>>,
+    /// Loaded data waiting for `dependencies` before it is constructed.
+    pub(crate) pending: Option<<L::Source as Source>::Output>,
+    pub(crate) dependencies: Vec<Dependency>,
+    /// The token of the running load. Results with another token are ignored, 0 means no load is running.
+    pub(crate) token: u64,
 }
 
 impl<A, L> AssetHandle<A, L>
@@ -41,19 +90,98 @@ where
         Self {
             path,
             asset: None,
+            tracker: None,
+            error: None,
+            waiting: Vec::new(),
             status: LoadStatus::NotLoaded,
             data,
+            modified: None,
+            supp: None,
+            reloading: false,
+            version: 0,
+            size: 0,
+            unreferenced_since: None,
+            pinned: false,
+            fallback: None,
+            pending: None,
+            dependencies: Vec::new(),
+            token: 0,
         }
     }
     pub(crate) fn unload(&mut self) {
         self.asset = None;
+        self.tracker = None;
+        self.error = None;
+        self.waiting.clear();
+        self.reloading = false;
+        self.pending = None;
+        self.dependencies.clear();
+        self.token = 0;
         self.status = LoadStatus::NotLoaded;
     }
-    pub(crate) fn set(&mut self, a: A::Structure) {
-        self.asset = Some(Arc::new(a));
+    /// Forget the running load. A loading Asset returns to NotLoaded, a reloading Asset stays loaded.
+    pub(crate) fn cancel(&mut self) {
+        if self.status.eq(&LoadStatus::Loading) {
+            return self.unload();
+        }
+        self.reloading = false;
+        self.pending = None;
+        self.dependencies.clear();
+        self.token = 0;
+    }
+    /// Returns true while a load or reload of the Asset is running.
+    pub(crate) fn busy(&self) -> bool {
+        self.status.eq(&LoadStatus::Loading) || self.reloading
+    }
+    pub(crate) fn set(&mut self, a: A::Structure, tracker: Arc<Tracker>) {
+        self.size = A::size(&a);
+        let a = Arc::new(a);
+        for s in self.waiting.drain(..) {
+            let _ = s.send(Ok(AssetRef::new(a.clone(), tracker.clone())));
+        }
+        self.asset = Some(a);
+        self.tracker = Some(tracker);
+        self.version += 1;
+        self.error = None;
         self.status = LoadStatus::Loaded;
     }
-    pub(crate) fn get(&self) -> Option<&Arc<A::Structure>> {
+    /// Swap in a reloaded Asset. Existing `AssetRef`s keep the previous Asset and still count as references.
+    pub(crate) fn replace(&mut self, a: A::Structure) {
+        self.size = A::size(&a);
+        self.asset = Some(Arc::new(a));
+        self.error = None;
+        self.reloading = false;
+        self.version += 1;
+    }
+    /// Keep the previous Asset if reloading it failed.
+    pub(crate) fn reload_failed(&mut self, e: Error) {
+        self.error = Some(e);
+        self.reloading = false;
+    }
+    pub(crate) fn fail(&mut self, e: Error) {
+        for s in self.waiting.drain(..) {
+            let _ = s.send(Err(e.clone()));
+        }
+        self.asset = None;
+        self.tracker = None;
+        self.error = Some(e);
+        self.status = LoadStatus::Failed;
+    }
+    /// Notify `s` once the Asset is loaded or failed.
+    pub(crate) fn wait(&mut self, s: Sender<Result<AssetRef<A::Structure>, Error>>) {
+        self.waiting.push(s);
+    }
+    pub(crate) fn get(&self) -> Option<AssetRef<A::Structure>> {
+        Some(AssetRef::new(self.asset.clone()?, self.tracker.clone()?))
+    }
+    pub(crate) fn arc(&self) -> Option<&Arc<A::Structure>> {
         self.asset.as_ref()
     }
+    /// Returns the number of `AssetRef`s alive for the loaded Asset.
+    pub(crate) fn references(&self) -> usize {
+        self.tracker.as_ref().map_or(0, |t| t.count())
+    }
+    pub(crate) fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
 }
@@ -1,9 +1,16 @@
-use crate::sources::Source;
-use crate::{loaders::Loader, Asset, Manager};
+use crate::{
+    dependency::SharedGraph,
+    loaders::{signal, LoadResult, Loader, LoaderHandle, LoaderRequest},
+    sources::SharedSource,
+    Asset, Manager,
+};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use std::{
     marker::PhantomData,
-    path::PathBuf,
-    sync::{mpsc::{channel, Receiver, Sender}},
+    sync::{
+        mpsc::{channel, Sender},
+        Arc,
+    },
 };
 /// Builder is used to Build Managers with a loading backend.
 /// construct a Builder, create Managers and finish by returning a loader.
@@ -12,9 +19,11 @@ pub struct Builder<L>
 where
     L: Loader,
 {
-    to_load_send: Sender<(usize, PathBuf, L::TransferSupplement)>,
-    to_load_recv: Receiver<(usize, PathBuf, L::TransferSupplement)>,
-    loaded: Vec<Sender<(PathBuf, <<L as Loader>::Source as Source>::Output)>>,
+    to_load_send: UnboundedSender<LoaderRequest<L>>,
+    to_load_recv: UnboundedReceiver<LoaderRequest<L>>,
+    loaded: Vec<Sender<LoadResult<L::Source>>>,
+    graph: SharedGraph<L::TransferSupplement>,
+    source: SharedSource<L::Source>,
     _phantom: PhantomData<L>,
 }
 
@@ -22,11 +31,13 @@ impl<L: Loader> Builder<L> {
     /// Construct a new, empty `Builder`.
     #[allow(unused)]
     pub fn new() -> Self {
-        let (to_load_send, to_load_recv) = channel();
+        let (to_load_send, to_load_recv) = unbounded();
         Self {
             to_load_send,
             to_load_recv,
             loaded: Vec::new(),
+            graph: SharedGraph::default(),
+            source: SharedSource::default(),
             _phantom: PhantomData::<L>,
         }
     }
@@ -36,12 +47,41 @@ impl<L: Loader> Builder<L> {
         let (s, r) = channel();
         let loader_id = self.loaded.len();
         self.loaded.push(s);
-        Manager::new(loader_id, self.to_load_send.clone(), r, data)
+        self.graph.lock().unwrap().add_manager();
+        Manager::new(
+            loader_id,
+            self.to_load_send.clone(),
+            r,
+            self.graph.clone(),
+            self.source.clone(),
+            data,
+        )
     }
 
-    /// Create the `Loader` associated with `Managers` built by this `Builder`.
+    /// Create the `Loader` associated with `Managers` built by this `Builder`
+    /// and the `LoaderHandle` used to shut it down. The Loader uses the default Source.
+    ///
+    /// The Loader stops once every Manager built by this Builder has been dropped
+    /// or `LoaderHandle::shutdown` is called.
+    #[allow(unused)]
+    pub fn finish_loader(self, data: L::LoaderSupplement) -> (L, LoaderHandle)
+    where
+        L::Source: Default,
+    {
+        self.finish_loader_with_source(L::Source::default(), data)
+    }
+    /// Create the `Loader` like `finish_loader`, loading with the given Source.
     #[allow(unused)]
-    pub fn finish_loader(self, data: L::LoaderSupplement) -> L {
-        L::new(self.to_load_recv, self.loaded, data)
+    pub fn finish_loader_with_source(self, source: L::Source, data: L::LoaderSupplement) -> (L, LoaderHandle) {
+        let (handle, signal) = signal();
+        let source = Arc::new(source);
+        let _ = self.source.set(source.clone());
+        (L::new(self.to_load_recv, self.loaded, signal, source, data), handle)
+    }
+}
+
+impl<L: Loader> Default for Builder<L> {
+    fn default() -> Self {
+        Self::new()
     }
 }
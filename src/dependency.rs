@@ -0,0 +1,157 @@
+use crate::Error;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// `ManagerId` identifies a `Manager` among the Managers created by the same `Builder`.
+/// It is returned by `Manager::manager_id`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ManagerId(pub(crate) usize);
+
+/// `Dependency` names an Asset by the `Manager` holding it and its path.
+/// It is returned by `Asset::dependencies`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Dependency {
+    manager: ManagerId,
+    path: PathBuf,
+}
+
+impl Dependency {
+    pub fn new<P: AsRef<Path>>(manager: ManagerId, path: P) -> Self {
+        Self {
+            manager,
+            path: path.as_ref().into(),
+        }
+    }
+    pub fn manager(&self) -> ManagerId {
+        self.manager
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// A message from the Manager of a dependency to the Manager of a dependent Asset.
+pub(crate) enum Notice {
+    /// The dependency of the Asset at the path is loaded.
+    Ready(PathBuf, Dependency),
+    /// The dependency of the Asset at the path failed to load.
+    Failed(PathBuf, Error),
+    /// A dependency of the Asset at the path has been reloaded, so it has to be reloaded too.
+    Reloaded(PathBuf),
+}
+
+struct Inbox<T> {
+    /// Paths to load with the TransferSupplement to load them with, together with the Asset that asked for them.
+    requests: Vec<(PathBuf, T, Dependency)>,
+    notices: Vec<Notice>,
+}
+
+/// The dependencies between the Assets of every Manager created by one `Builder`.
+/// `T` is the TransferSupplement of the Loader.
+///
+/// Managers only exchange messages through the graph, each one handles its inbox during `maintain`.
+pub(crate) struct Graph<T> {
+    inboxes: Vec<Inbox<T>>,
+    dependents: HashMap<Dependency, Vec<Dependency>>,
+}
+
+pub(crate) type SharedGraph<T> = Arc<Mutex<Graph<T>>>;
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self {
+            inboxes: Vec::new(),
+            dependents: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Clone> Graph<T> {
+    pub(crate) fn add_manager(&mut self) {
+        self.inboxes.push(Inbox {
+            requests: Vec::new(),
+            notices: Vec::new(),
+        });
+    }
+    /// Record that `dependent` needs `dependencies` and ask their Managers to load them.
+    /// Dependencies recorded by an earlier load of `dependent` are replaced.
+    ///
+    /// Fails with `Error::DependencyCycle` if a dependency depends on `dependent` itself.
+    pub(crate) fn request(&mut self, dependent: &Dependency, dependencies: &[(Dependency, T)]) -> Result<(), Error> {
+        if let Some((d, _)) = dependencies.iter().find(|(d, _)| d.manager.0 >= self.inboxes.len()) {
+            return Err(Error::NotRegistered(d.path.clone()));
+        }
+        if let Some((d, _)) = dependencies.iter().find(|(d, _)| self.depends_on(d, dependent)) {
+            return Err(Error::DependencyCycle(d.path.clone()));
+        }
+        for list in self.dependents.values_mut() {
+            list.retain(|d| d.ne(dependent));
+        }
+        for (d, supp) in dependencies {
+            let list = self.dependents.entry(d.clone()).or_default();
+            if !list.contains(dependent) {
+                list.push(dependent.clone());
+            }
+            self.inboxes[d.manager.0]
+                .requests
+                .push((d.path.clone(), supp.clone(), dependent.clone()));
+        }
+        Ok(())
+    }
+    /// Returns true if `dependency` is `dependent` or depends on it, found by walking the dependents of `dependent`.
+    fn depends_on(&self, dependency: &Dependency, dependent: &Dependency) -> bool {
+        let mut seen = HashSet::new();
+        let mut stack = vec![dependent];
+        while let Some(d) = stack.pop() {
+            if d == dependency {
+                return true;
+            }
+            if seen.insert(d) {
+                stack.extend(self.dependents.get(d).into_iter().flatten());
+            }
+        }
+        false
+    }
+    /// Forget the dependencies of `dependent`, e.g. because it has been unloaded or dropped.
+    pub(crate) fn remove(&mut self, dependent: &Dependency) {
+        self.dependents.retain(|_, list| {
+            list.retain(|d| d.ne(dependent));
+            !list.is_empty()
+        });
+    }
+    /// Tell `dependent` that `dependency` is loaded or why it failed.
+    pub(crate) fn answer(&mut self, dependent: Dependency, dependency: Dependency, result: Result<(), Error>) {
+        let notice = match result {
+            Ok(()) => Notice::Ready(dependent.path, dependency),
+            Err(e) => Notice::Failed(dependent.path, e),
+        };
+        self.inboxes[dependent.manager.0].notices.push(notice);
+    }
+    /// Tell every dependent of `dependency` that it finished loading.
+    pub(crate) fn loaded(&mut self, dependency: &Dependency, result: Result<(), &Error>) {
+        for dependent in self.dependents.get(dependency).into_iter().flatten() {
+            let notice = match result {
+                Ok(()) => Notice::Ready(dependent.path.clone(), dependency.clone()),
+                Err(e) => Notice::Failed(dependent.path.clone(), e.clone()),
+            };
+            self.inboxes[dependent.manager.0].notices.push(notice);
+        }
+    }
+    /// Tell every dependent of `dependency` that it has been reloaded.
+    pub(crate) fn reloaded(&mut self, dependency: &Dependency) {
+        for dependent in self.dependents.get(dependency).into_iter().flatten() {
+            self.inboxes[dependent.manager.0]
+                .notices
+                .push(Notice::Reloaded(dependent.path.clone()));
+        }
+    }
+    /// Take the requests and notices addressed to a Manager.
+    pub(crate) fn take(&mut self, manager: ManagerId) -> (Vec<(PathBuf, T, Dependency)>, Vec<Notice>) {
+        let inbox = &mut self.inboxes[manager.0];
+        (mem::take(&mut inbox.requests), mem::take(&mut inbox.notices))
+    }
+}
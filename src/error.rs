@@ -0,0 +1,91 @@
+use std::{
+    error::Error as StdError,
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Error type returned by `Source::load` and `Asset::construct`.
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// Every error returned by this crate. Each variant except `StaleId` and `NoManager` carries the path of the Asset it belongs to.
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// The path is not known to the Manager. It has to be inserted first.
+    NotRegistered(PathBuf),
+    /// The `AssetId` belongs to an Asset that has been dropped from its Manager.
+    StaleId,
+    /// The `AssetServer` has no Manager for the Asset type with the given name.
+    NoManager(&'static str),
+    /// There is no file at the path. Returned when the Source fails with `io::ErrorKind::NotFound`.
+    FileNotFound(PathBuf),
+    /// The Asset is already being loaded.
+    AlreadyLoading(PathBuf),
+    /// The Loader associated with the Manager is gone.
+    LoaderDisconnected(PathBuf),
+    /// The Asset was unloaded or dropped before it finished loading.
+    Cancelled(PathBuf),
+    /// The Source could not load the Asset.
+    SourceFailed(PathBuf, Arc<dyn StdError + Send + Sync>),
+    /// `Asset::construct` could not decode the loaded data.
+    DecodeFailed(PathBuf, Arc<dyn StdError + Send + Sync>),
+    /// A dependency of the Asset failed to load. Carries the error of the dependency.
+    DependencyFailed(PathBuf, Box<Error>),
+    /// The dependency at the path depends on the Asset requesting it, directly or through other Assets.
+    DependencyCycle(PathBuf),
+}
+
+impl Error {
+    pub(crate) fn source_failed(path: PathBuf, e: BoxError) -> Self {
+        match e.downcast_ref::<io::Error>() {
+            Some(io) if io.kind() == io::ErrorKind::NotFound => Error::FileNotFound(path),
+            _ => Error::SourceFailed(path, e.into()),
+        }
+    }
+    pub(crate) fn decode_failed(path: PathBuf, e: BoxError) -> Self {
+        Error::DecodeFailed(path, e.into())
+    }
+    /// Returns the path of the Asset the error belongs to, or None for `StaleId` and `NoManager`.
+    pub fn path(&self) -> Option<&Path> {
+        Some(match self {
+            Error::StaleId | Error::NoManager(_) => return None,
+            Error::NotRegistered(p)
+            | Error::FileNotFound(p)
+            | Error::AlreadyLoading(p)
+            | Error::LoaderDisconnected(p)
+            | Error::Cancelled(p)
+            | Error::SourceFailed(p, _)
+            | Error::DecodeFailed(p, _)
+            | Error::DependencyFailed(p, _)
+            | Error::DependencyCycle(p) => p,
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotRegistered(p) => write!(f, "Entry not found! {:?}", p),
+            Error::StaleId => write!(f, "Asset has been dropped!"),
+            Error::NoManager(t) => write!(f, "No Manager for {}!", t),
+            Error::FileNotFound(p) => write!(f, "File not found! {:?}", p),
+            Error::AlreadyLoading(p) => write!(f, "Asset already loading! {:?}", p),
+            Error::LoaderDisconnected(p) => write!(f, "Loader disconnected! {:?}", p),
+            Error::Cancelled(p) => write!(f, "Loading cancelled! {:?}", p),
+            Error::SourceFailed(p, e) => write!(f, "Loading {:?} failed: {}", p, e),
+            Error::DecodeFailed(p, e) => write!(f, "Decoding {:?} failed: {}", p, e),
+            Error::DependencyFailed(p, e) => write!(f, "Dependency of {:?} failed: {}", p, e),
+            Error::DependencyCycle(p) => write!(f, "Dependency cycle through {:?}!", p),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::SourceFailed(_, e) | Error::DecodeFailed(_, e) => Some(e.as_ref()),
+            Error::DependencyFailed(_, e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
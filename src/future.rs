@@ -0,0 +1,42 @@
+use crate::{AssetRef, Error};
+use futures::channel::oneshot::{channel, Receiver, Sender};
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// AssetFuture is returned by `Manager::load_async` and resolves once the Asset is loaded and constructed.
+///
+/// The Manager constructs Assets during `maintain`, so it has to be maintained for the future to complete.
+pub struct AssetFuture<T> {
+    path: PathBuf,
+    recv: Receiver<Result<AssetRef<T>, Error>>,
+}
+
+impl<T> AssetFuture<T> {
+    /// Create a pending future and the Sender that completes it.
+    pub(crate) fn pending(path: PathBuf) -> (Self, Sender<Result<AssetRef<T>, Error>>) {
+        let (s, recv) = channel();
+        (Self { path, recv }, s)
+    }
+    /// Create an already completed future.
+    pub(crate) fn ready(result: Result<AssetRef<T>, Error>) -> Self {
+        let (future, s) = Self::pending(PathBuf::new());
+        let _ = s.send(result);
+        future
+    }
+}
+
+impl<T> Future for AssetFuture<T> {
+    type Output = Result<AssetRef<T>, Error>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.recv).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Error::Cancelled(this.path.clone()))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Error;
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+/// `AssetId` is a small copyable key of an Asset inside a `Manager`, returned by `Manager::insert`.
+///
+/// Ids carry a generation, so an id of a dropped Asset is detected as stale
+/// instead of aliasing an Asset inserted later with the same path.
+pub struct AssetId<A> {
+    index: u32,
+    generation: u32,
+    _phantom: PhantomData<fn() -> A>,
+}
+
+impl<A> AssetId<A> {
+    fn new(index: u32, generation: u32) -> Self {
+        Self {
+            index,
+            generation,
+            _phantom: PhantomData,
+        }
+    }
+    pub(crate) fn to_raw(self) -> (u32, u32) {
+        (self.index, self.generation)
+    }
+    pub(crate) fn from_raw((index, generation): (u32, u32)) -> Self {
+        Self::new(index, generation)
+    }
+}
+
+impl<A> Clone for AssetId<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for AssetId<A> {}
+
+impl<A> PartialEq for AssetId<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<A> Eq for AssetId<A> {}
+
+impl<A> Hash for AssetId<A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<A> fmt::Debug for AssetId<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AssetId({}v{})", self.index, self.generation)
+    }
+}
+
+/// Anything that identifies an Asset inside a `Manager`: its `AssetId` or its path.
+pub trait AssetKey<A> {
+    /// Look up the `AssetId` of the key.
+    fn resolve(&self, ids: &HashMap<PathBuf, AssetId<A>>) -> Result<AssetId<A>, Error>;
+}
+
+impl<A> AssetKey<A> for AssetId<A> {
+    fn resolve(&self, _: &HashMap<PathBuf, AssetId<A>>) -> Result<AssetId<A>, Error> {
+        Ok(*self)
+    }
+}
+
+impl<A, P: AsRef<Path>> AssetKey<A> for P {
+    fn resolve(&self, ids: &HashMap<PathBuf, AssetId<A>>) -> Result<AssetId<A>, Error> {
+        ids.get(self.as_ref())
+            .copied()
+            .ok_or_else(|| Error::NotRegistered(self.as_ref().into()))
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Generational storage addressed by `AssetId`s. Free slots are reused with a new generation.
+pub(crate) struct Slots<A, T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    _phantom: PhantomData<fn() -> A>,
+}
+
+impl<A, T> Slots<A, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+    pub(crate) fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+    pub(crate) fn insert(&mut self, value: T) -> AssetId<A> {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.generation += 1;
+                slot.value = Some(value);
+                AssetId::new(index, slot.generation)
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                AssetId::new(self.slots.len() as u32 - 1, 0)
+            }
+        }
+    }
+    pub(crate) fn remove(&mut self, id: AssetId<A>) -> Option<T> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        self.free.push(id.index);
+        Some(value)
+    }
+    pub(crate) fn get(&self, id: AssetId<A>) -> Option<&T> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?
+            .value
+            .as_ref()
+    }
+    pub(crate) fn get_mut(&mut self, id: AssetId<A>) -> Option<&mut T> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?
+            .value
+            .as_mut()
+    }
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (AssetId<A>, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let id = AssetId::new(index as u32, slot.generation);
+            slot.value.as_ref().map(|value| (id, value))
+        })
+    }
+    pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+}
@@ -1,12 +1,24 @@
 mod asset;
 mod builder;
+mod dependency;
+mod error;
+mod future;
+mod id;
 mod loaders;
 mod manager;
+mod reference;
+mod server;
 mod sources;
 pub use asset::Asset;
 pub use builder::Builder;
+pub use dependency::{Dependency, ManagerId};
+pub use error::{BoxError, Error};
+pub use future::AssetFuture;
+pub use id::{AssetId, AssetKey};
 pub use loaders::*;
 pub use sources::*;
 pub use manager::Manager;
+pub use reference::{AssetRef, WeakAssetRef};
+pub use server::AssetServer;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,84 @@
+use futures::{
+    channel::oneshot::{channel, Receiver, Sender},
+    task::{AtomicWaker, Context, Poll},
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+struct Shared {
+    shutdown: AtomicBool,
+    waker: AtomicWaker,
+}
+
+/// LoaderHandle is returned by `Builder::finish_loader` and controls the lifetime of its Loader.
+pub struct LoaderHandle {
+    shared: Arc<Shared>,
+    finished: Receiver<()>,
+}
+
+/// LoaderSignal is handed to a `Loader` on construction. The Loader has to watch it for shutdown requests
+/// and drop it when it stops, which completes the associated `LoaderHandle`.
+pub struct LoaderSignal {
+    shared: Arc<Shared>,
+    _finished: Sender<()>,
+}
+
+/// Create a connected `LoaderHandle` and `LoaderSignal`.
+pub(crate) fn signal() -> (LoaderHandle, LoaderSignal) {
+    let shared = Arc::new(Shared {
+        shutdown: AtomicBool::new(false),
+        waker: AtomicWaker::new(),
+    });
+    let (s, r) = channel();
+    (
+        LoaderHandle {
+            shared: shared.clone(),
+            finished: r,
+        },
+        LoaderSignal {
+            shared,
+            _finished: s,
+        },
+    )
+}
+
+impl LoaderHandle {
+    /// Ask the Loader to stop.
+    ///
+    /// The Loader stops accepting requests, finishes the loads already in flight and
+    /// reports every request it did not start as `Error::LoaderDisconnected`.
+    pub fn shutdown(&self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        self.shared.waker.wake();
+    }
+    /// Returns true once the Loader has stopped.
+    pub fn is_finished(&mut self) -> bool {
+        !matches!(self.finished.try_recv(), Ok(None))
+    }
+    /// Wait for the Loader to stop.
+    pub async fn join(self) {
+        let _ = self.finished.await;
+    }
+}
+
+impl LoaderSignal {
+    /// Returns true if a shutdown has been requested.
+    pub fn is_shutdown(&self) -> bool {
+        self.shared.shutdown.load(Ordering::SeqCst)
+    }
+    /// Returns `Poll::Ready` if a shutdown has been requested,
+    /// otherwise the current task will be woken once it is.
+    pub fn poll_shutdown(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_shutdown() {
+            return Poll::Ready(());
+        }
+        self.shared.waker.register(cx.waker());
+        if self.is_shutdown() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
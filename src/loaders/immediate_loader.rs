@@ -0,0 +1,136 @@
+use crate::{
+    loaders::{LoadResult, LoaderRequest, LoaderSignal, Queue, Request},
+    sources::{DiskSource, Source},
+};
+use futures::{
+    channel::mpsc::UnboundedReceiver,
+    stream::StreamExt,
+    task::{waker_ref, ArcWake, Context, Poll},
+};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc, Mutex,
+    },
+};
+
+struct Inner<S: Source> {
+    to_load: Option<UnboundedReceiver<Request<S::Supplement, S::Output>>>,
+    queue: Queue<S::Supplement, S::Output>,
+    loaded: Vec<Sender<LoadResult<S>>>,
+    signal: Option<LoaderSignal>,
+    source: Arc<S>,
+}
+
+/// The waker registered on the request channel. Waking it performs the pending loads.
+struct Dispatch<S: Source> {
+    pending: AtomicBool,
+    inner: Mutex<Inner<S>>,
+}
+
+///ImmediateLoader performs every load inline on the thread that calls `Manager::load`.
+///There is no background task, so the Asset is available after the next call to `maintain`.
+///Intended for tools and tests.
+pub struct ImmediateLoader<S = DiskSource>
+where
+    S: Source,
+{
+    _dispatch: Arc<Dispatch<S>>,
+}
+
+impl<S> super::Loader for ImmediateLoader<S>
+where
+    S: Source<Input = PathBuf> + Send + Sync + 'static,
+    S::Supplement: Send + Sync + Clone,
+    S::Output: Send,
+{
+    type Source = S;
+    type TransferSupplement = S::Supplement;
+    type LoaderSupplement = ();
+    fn new(
+        to_load: UnboundedReceiver<LoaderRequest<Self>>,
+        loaded: Vec<Sender<LoadResult<Self::Source>>>,
+        signal: LoaderSignal,
+        source: Arc<Self::Source>,
+        _: Self::LoaderSupplement,
+    ) -> Self {
+        let dispatch = Arc::new(Dispatch {
+            pending: AtomicBool::new(false),
+            inner: Mutex::new(Inner {
+                to_load: Some(to_load),
+                queue: Queue::new(),
+                loaded,
+                signal: Some(signal),
+                source,
+            }),
+        });
+        // registers the waker and handles requests sent before the loader existed
+        ArcWake::wake_by_ref(&dispatch);
+        Self {
+            _dispatch: dispatch,
+        }
+    }
+}
+
+impl<S> ArcWake for Dispatch<S>
+where
+    S: Source<Input = PathBuf> + Send + Sync + 'static,
+    S::Supplement: Send,
+    S::Output: Send,
+{
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        // a wake while another thread (or a re-entrant registration) holds the lock is picked up by the holder
+        arc_self.pending.store(true, Ordering::SeqCst);
+        while arc_self.pending.load(Ordering::SeqCst) {
+            let mut inner = match arc_self.inner.try_lock() {
+                Ok(inner) => inner,
+                Err(_) => return,
+            };
+            arc_self.pending.store(false, Ordering::SeqCst);
+            let waker = waker_ref(arc_self);
+            inner.process(&mut Context::from_waker(&waker));
+        }
+    }
+}
+
+impl<S: Source<Input = PathBuf>> Inner<S> {
+    fn process(&mut self, cx: &mut Context<'_>) {
+        let shutdown = match &self.signal {
+            Some(signal) => signal.poll_shutdown(cx).is_ready(),
+            None => return,
+        };
+        let to_load = match self.to_load.as_mut() {
+            Some(to_load) => to_load,
+            None => return,
+        };
+        if shutdown {
+            to_load.close();
+        }
+        // every request available is queued first, so the loads run by priority
+        let closed = loop {
+            match to_load.poll_next_unpin(cx) {
+                Poll::Ready(Some(request)) => self.queue.push(request),
+                Poll::Ready(None) => break true,
+                Poll::Pending => break false,
+            }
+        };
+        while let Some(job) = self.queue.pop() {
+            let manager_idx = job.manager;
+            let result = if shutdown {
+                job.disconnected()
+            } else {
+                job.load(&*self.source).1
+            };
+            if let Some(sender) = self.loaded.get(manager_idx) {
+                if sender.send(result).is_err() {}
+            }
+        }
+        if closed {
+            // dropping the receiver and the signal releases the registered wakers
+            self.to_load = None;
+            self.signal = None;
+        }
+    }
+}
@@ -1,54 +1,205 @@
 use crate::{
+    loaders::{timer::Timer, LoadResult, LoaderRequest, LoaderSignal, Queue, Request},
     sources::{DiskSource, Source},
-    Loader,
 };
-use futures::stream::{FuturesUnordered, StreamExt};
+use futures::{
+    channel::{mpsc::UnboundedReceiver, oneshot},
+    future::poll_fn,
+    stream::{FuturesUnordered, StreamExt},
+    task::Poll,
+};
 use std::{
     path::PathBuf,
-    sync::mpsc::{Receiver, Sender},
+    sync::{
+        mpsc::{channel, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
+
+enum Event<R, D> {
+    Start(R),
+    Done(D),
+}
+
+type Work = Box<dyn FnOnce() + Send>;
+
+/// The worker threads performing the loads started by `MemoryLoader::run`. They stop once the Pool is dropped.
+struct Pool {
+    work: Sender<Work>,
+}
+
+impl Pool {
+    fn new(threads: usize) -> Self {
+        let (work, recv) = channel::<Work>();
+        let recv = Arc::new(Mutex::new(recv));
+        for _ in 0..threads {
+            let recv = recv.clone();
+            std::thread::spawn(move || loop {
+                let work = recv.lock().unwrap().recv();
+                match work {
+                    Ok(work) => work(),
+                    Err(_) => break,
+                }
+            });
+        }
+        Self { work }
+    }
+    fn run(&self, work: impl FnOnce() + Send + 'static) {
+        let _ = self.work.send(Box::new(work));
+    }
+}
+
+/// The LoaderSupplement of the `MemoryLoader`. By default loads are not throttled
+/// and run on one worker thread per CPU.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct MemoryLoaderConfig {
+    max_in_flight: Option<usize>,
+    bytes_per_second: Option<u64>,
+}
+
+impl MemoryLoaderConfig {
+    /// Limit the number of loads running at the same time, each on its own worker thread.
+    /// At most one worker per CPU is started, so the limit is capped by the number of CPUs.
+    /// Further requests wait in the queue.
+    pub fn max_in_flight(mut self, loads: usize) -> Self {
+        self.max_in_flight = Some(loads.max(1));
+        self
+    }
+    /// Limit the bytes loaded per second. After a load finished, the next load starts once
+    /// its bytes would have taken that long at the given rate. The bytes are measured by `Source::size`.
+    pub fn bytes_per_second(mut self, bytes: u64) -> Self {
+        self.bytes_per_second = Some(bytes.max(1));
+        self
+    }
+}
+
 ///MemoryLoader recieves assets to load from the associated Managers, then loads and returns them asynchronous.
-pub struct MemoryLoader {
-    to_load: Receiver<(usize, PathBuf, <Self as Loader>::TransferSupplement)>,
-    loaded: Vec<Sender<(PathBuf, Vec<u8>)>>,
+pub struct MemoryLoader<S = DiskSource>
+where
+    S: Source,
+{
+    to_load: UnboundedReceiver<Request<S::Supplement, S::Output>>,
+    loaded: Vec<Sender<LoadResult<S>>>,
+    signal: LoaderSignal,
+    source: Arc<S>,
+    config: MemoryLoaderConfig,
 }
 
-impl super::Loader for MemoryLoader {
-    type Source = DiskSource;
-    type TransferSupplement = ();
-    type LoaderSupplement = ();
+impl<S> super::Loader for MemoryLoader<S>
+where
+    S: Source<Input = PathBuf>,
+    S::Supplement: Send + Sync + Clone,
+{
+    type Source = S;
+    type TransferSupplement = S::Supplement;
+    type LoaderSupplement = MemoryLoaderConfig;
     fn new(
-        to_load: Receiver<(usize, PathBuf, Self::TransferSupplement)>,
-        loaded: Vec<Sender<(PathBuf, <Self::Source as Source>::Output)>>,
-        _: Self::LoaderSupplement,
+        to_load: UnboundedReceiver<LoaderRequest<Self>>,
+        loaded: Vec<Sender<LoadResult<Self::Source>>>,
+        signal: LoaderSignal,
+        source: Arc<Self::Source>,
+        config: Self::LoaderSupplement,
     ) -> Self {
-        Self { to_load, loaded }
+        Self {
+            to_load,
+            loaded,
+            signal,
+            source,
+            config,
+        }
     }
 }
 
-impl MemoryLoader {
-    #[allow(unused)]
-    pub(crate) fn new(
-        to_load: Receiver<(usize, PathBuf, <Self as Loader>::TransferSupplement)>,
-        loaded: Vec<Sender<(PathBuf, Vec<u8>)>>,
-    ) -> Self {
-        Self { to_load, loaded }
-    }
+impl<S> MemoryLoader<S>
+where
+    S: Source<Input = PathBuf> + Send + Sync + 'static,
+    S::Supplement: Send + Sync + Clone + 'static,
+    S::Output: Send + 'static,
+{
     /// run the async load loop
+    ///
+    /// The loop waits until either a new request arrives or a load completes.
+    /// The loads are performed on worker threads, so the loop never blocks on the Source.
+    /// Received requests are queued and started by priority, as far as the `MemoryLoaderConfig` allows.
+    /// It returns once every Manager has been dropped or the `LoaderHandle` requested a shutdown,
+    /// and all pending loads are finished.
     #[allow(unused)]
     pub async fn run(mut self) {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        // loads that are not running stay in the queue, where they can still be reprioritized or cancelled
+        let limit = self.config.max_in_flight.map_or(cpus, |max| max.min(cpus));
+        let pool = Pool::new(limit);
+        let timer = Timer::new();
         let mut loading = FuturesUnordered::new();
+        let mut queue = Queue::new();
+        let mut shutdown = false;
+        let mut closed = false;
+        let mut throttled: Option<Instant> = None;
         loop {
-            self.to_load.try_iter().for_each(|(id, p, supp)| {
-                loading.push(async move {
-                    (id, p.clone(), <<Self as Loader>::Source as Source>::load(p))
-                })
-            });
-
-            if let Some((manager_idx, path, Ok(bytes))) = loading.next().await {
-                if let Some(sender) = self.loaded.get_mut(manager_idx) {
-                    if sender.send((path, bytes)).is_err() {}
+            let event = poll_fn(|cx| {
+                if !shutdown && self.signal.poll_shutdown(cx).is_ready() {
+                    shutdown = true;
+                    closed = true;
+                    self.to_load.close();
+                    while let Poll::Ready(Some(request)) = self.to_load.poll_next_unpin(cx) {
+                        queue.push(request);
+                    }
+                    for job in queue.drain() {
+                        if let Some(sender) = self.loaded.get(job.manager) {
+                            let _ = sender.send(job.disconnected());
+                        }
+                    }
+                }
+                while !closed {
+                    match self.to_load.poll_next_unpin(cx) {
+                        Poll::Ready(Some(request)) => queue.push(request),
+                        Poll::Ready(None) => closed = true,
+                        Poll::Pending => break,
+                    }
+                }
+                loop {
+                    match loading.poll_next_unpin(cx) {
+                        Poll::Ready(Some(Ok(done))) => return Poll::Ready(Some(Event::Done(done))),
+                        // the worker stopped without a result
+                        Poll::Ready(Some(Err(oneshot::Canceled))) => continue,
+                        Poll::Ready(None) if closed && queue.is_empty() => return Poll::Ready(None),
+                        _ => break,
+                    }
+                }
+                if loading.len() >= limit || queue.is_empty() {
+                    return Poll::Pending;
+                }
+                if let Some(at) = throttled {
+                    if timer.poll_until(at, cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                }
+                Poll::Ready(queue.pop().map(Event::Start))
+            })
+            .await;
+            match event {
+                Some(Event::Start(job)) => {
+                    let source = self.source.clone();
+                    let (done, result) = oneshot::channel();
+                    pool.run(move || {
+                        let manager = job.manager;
+                        let (bytes, result) = job.load(&*source);
+                        let _ = done.send((manager, bytes, result));
+                    });
+                    loading.push(result);
+                }
+                Some(Event::Done((manager_idx, bytes, result))) => {
+                    if let Some(rate) = self.config.bytes_per_second {
+                        let now = Instant::now();
+                        let start = throttled.map_or(now, |at| at.max(now));
+                        throttled = Some(start + Duration::from_secs_f64(bytes as f64 / rate as f64));
+                    }
+                    if let Some(sender) = self.loaded.get_mut(manager_idx) {
+                        if sender.send(result).is_err() {}
+                    }
                 }
+                None => break,
             }
         }
     }
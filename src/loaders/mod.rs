@@ -1,24 +1,55 @@
+mod handle;
+mod immediate_loader;
 mod memory_loader;
-use crate::sources::Source;
-pub use memory_loader::MemoryLoader;
+mod queue;
+mod thread_pool_loader;
+mod timer;
+use crate::{sources::Source, Error};
+pub(crate) use handle::signal;
+pub use handle::{LoaderHandle, LoaderSignal};
+pub use immediate_loader::ImmediateLoader;
+pub use memory_loader::{MemoryLoader, MemoryLoaderConfig};
+pub(crate) use queue::Queue;
+pub use queue::{Construct, Priority, Request};
+pub use thread_pool_loader::ThreadPoolLoader;
+use futures::channel::mpsc::UnboundedReceiver;
 use std::{
+    any::Any,
     path::PathBuf,
-    sync::mpsc::{Receiver, Sender},
+    sync::{mpsc::Sender, Arc},
 };
+/// The message a `Loader` sends back to a `Manager`: the loaded path, the token of the request
+/// and the loaded data or the reason it failed.
+pub type LoadResult<S> = (PathBuf, u64, Result<Payload<<S as Source>::Output>, Error>);
+/// The Requests a `Loader` receives from its Managers.
+pub type LoaderRequest<L> = Request<<L as Loader>::TransferSupplement, <<L as Loader>::Source as Source>::Output>;
+
+/// The data a `Loader` sends back to a `Manager`.
+pub enum Payload<O> {
+    /// The output of the Source, constructed by the Manager.
+    Raw(O),
+    /// The Asset, already constructed by the Loader.
+    Constructed(Box<dyn Any + Send>),
+}
+
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub enum LoadStatus {
     NotLoaded,
     Loading,
     Loaded,
+    Failed,
 }
 
 pub trait Loader {
+    /// The Source performing the loads, see `Builder::finish_loader_with_source`.
     type Source: Source;
-    type TransferSupplement: Send + Sync;
+    type TransferSupplement: Send + Sync + Clone;
     type LoaderSupplement;
     fn new(
-        to_load: Receiver<(usize, PathBuf, Self::TransferSupplement)>,
-        loaded: Vec<Sender<(PathBuf, <Self::Source as Source>::Output)>>,
+        to_load: UnboundedReceiver<LoaderRequest<Self>>,
+        loaded: Vec<Sender<LoadResult<Self::Source>>>,
+        signal: LoaderSignal,
+        source: Arc<Self::Source>,
         data: Self::LoaderSupplement,
     ) -> Self;
 }
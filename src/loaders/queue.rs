@@ -0,0 +1,186 @@
+use crate::{
+    loaders::{LoadResult, Payload},
+    sources::Source,
+    BoxError, Error,
+};
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+/// The priority of a load. Requests with a higher priority are loaded first, equal priorities in arrival order.
+pub type Priority = i32;
+
+/// Constructs an Asset from the output of a Source on the Loader, see `Manager::construct_on_loader`.
+pub type Construct<O> = Box<dyn FnOnce(O) -> Result<Payload<O>, Error> + Send>;
+
+/// A message from a `Manager` to its `Loader`. `T` is the TransferSupplement, `O` the output of the Source.
+///
+/// `manager` is the index of the Managers Sender in the `loaded` list handed to `Loader::new`.
+pub enum Request<T, O> {
+    /// Load the path and send the result to the Manager together with the `token`.
+    /// If `construct` is set, the Loader passes the loaded data through it before sending.
+    Load {
+        manager: usize,
+        path: PathBuf,
+        supp: T,
+        priority: Priority,
+        token: u64,
+        construct: Option<Construct<O>>,
+    },
+    /// Change the priority of a queued load of the path that has not started yet.
+    Reprioritize {
+        manager: usize,
+        path: PathBuf,
+        priority: Priority,
+    },
+    /// Remove the queued loads of the path. Loads that already started are not interrupted.
+    Cancel { manager: usize, path: PathBuf },
+}
+
+/// A load taken from the `Queue`.
+pub(crate) struct Job<T, O> {
+    pub(crate) manager: usize,
+    pub(crate) path: PathBuf,
+    pub(crate) token: u64,
+    pub(crate) supp: T,
+    construct: Option<Construct<O>>,
+}
+
+impl<T, O> Job<T, O> {
+    /// Load the job from `source` and complete it. Also returns the `Source::size` of the loaded data.
+    /// A panic while loading or constructing fails the load with `Error::SourceFailed` instead of stopping the Loader.
+    pub(crate) fn load<S>(self, source: &S) -> (usize, LoadResult<S>)
+    where
+        S: Source<Input = PathBuf, Supplement = T, Output = O>,
+    {
+        let (path, token) = (self.path.clone(), self.token);
+        catch_unwind(AssertUnwindSafe(move || {
+            let output = source.load(self.path.clone(), &self.supp);
+            let bytes = output.as_ref().map_or(0, S::size);
+            (bytes, self.complete(output))
+        }))
+        .unwrap_or_else(|_| {
+            let e = Error::source_failed(path.clone(), "loading panicked".into());
+            (0, (path, token, Err(e)))
+        })
+    }
+    /// Turn the output of the Source into the result for the Manager, constructing the Asset if requested.
+    fn complete(self, output: Result<O, BoxError>) -> (PathBuf, u64, Result<Payload<O>, Error>) {
+        let Job {
+            path,
+            token,
+            construct,
+            ..
+        } = self;
+        let result = output
+            .map_err(|e| Error::source_failed(path.clone(), e))
+            .and_then(|o| match construct {
+                Some(construct) => construct(o),
+                None => Ok(Payload::Raw(o)),
+            });
+        (path, token, result)
+    }
+    /// The result for the Manager if the Loader stopped before starting the load.
+    pub(crate) fn disconnected(self) -> (PathBuf, u64, Result<Payload<O>, Error>) {
+        (self.path.clone(), self.token, Err(Error::LoaderDisconnected(self.path)))
+    }
+}
+
+struct Entry<T, O> {
+    priority: Priority,
+    seq: u64,
+    job: Job<T, O>,
+}
+
+impl<T, O> PartialEq for Entry<T, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T, O> Eq for Entry<T, O> {}
+
+impl<T, O> PartialOrd for Entry<T, O> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, O> Ord for Entry<T, O> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// The loads a Loader received but did not start yet, ordered by priority.
+pub(crate) struct Queue<T, O> {
+    heap: BinaryHeap<Entry<T, O>>,
+    seq: u64,
+}
+
+impl<T, O> Queue<T, O> {
+    pub(crate) fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            seq: 0,
+        }
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+    /// Queue a load, change the priority of a queued one or remove it.
+    pub(crate) fn push(&mut self, request: Request<T, O>) {
+        match request {
+            Request::Load {
+                manager,
+                path,
+                supp,
+                priority,
+                token,
+                construct,
+            } => {
+                self.seq += 1;
+                self.heap.push(Entry {
+                    priority,
+                    seq: self.seq,
+                    job: Job {
+                        manager,
+                        path,
+                        token,
+                        supp,
+                        construct,
+                    },
+                });
+            }
+            Request::Reprioritize {
+                manager,
+                path,
+                priority,
+            } => {
+                let mut entries = std::mem::take(&mut self.heap).into_vec();
+                for e in entries.iter_mut() {
+                    if e.job.manager == manager && e.job.path == path {
+                        e.priority = priority;
+                    }
+                }
+                self.heap = entries.into();
+            }
+            Request::Cancel { manager, path } => {
+                self.heap.retain(|e| e.job.manager != manager || e.job.path != path);
+            }
+        }
+    }
+    /// Take the queued load with the highest priority.
+    pub(crate) fn pop(&mut self) -> Option<Job<T, O>> {
+        self.heap.pop().map(|e| e.job)
+    }
+    /// Take every queued load.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = Job<T, O>> + '_ {
+        self.heap.drain().map(|e| e.job)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    loaders::{LoadResult, LoaderRequest, LoaderSignal, Queue, Request},
+    sources::{DiskSource, Source},
+};
+use futures::{
+    channel::mpsc::UnboundedReceiver, executor::block_on, future::poll_fn, stream::StreamExt,
+    task::Poll,
+};
+use std::{
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc, Mutex},
+    thread::JoinHandle,
+};
+
+/// The requests shared by the workers: the channel and the loads received but not started yet.
+struct Requests<S: Source> {
+    to_load: UnboundedReceiver<Request<S::Supplement, S::Output>>,
+    queue: Queue<S::Supplement, S::Output>,
+}
+
+///ThreadPoolLoader recieves assets to load from the associated Managers and loads them in parallel on its own worker threads.
+///It does not need an async runtime. The LoaderSupplement is the number of worker threads.
+///The Source is shared by the workers.
+pub struct ThreadPoolLoader<S = DiskSource> {
+    threads: Vec<JoinHandle<()>>,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<S> super::Loader for ThreadPoolLoader<S>
+where
+    S: Source<Input = PathBuf> + Send + Sync + 'static,
+    S::Supplement: Send + Sync + Clone,
+    S::Output: Send,
+{
+    type Source = S;
+    type TransferSupplement = S::Supplement;
+    type LoaderSupplement = usize;
+    fn new(
+        to_load: UnboundedReceiver<LoaderRequest<Self>>,
+        loaded: Vec<Sender<LoadResult<Self::Source>>>,
+        signal: LoaderSignal,
+        source: Arc<Self::Source>,
+        threads: Self::LoaderSupplement,
+    ) -> Self {
+        let to_load = Arc::new(Mutex::new(Requests {
+            to_load,
+            queue: Queue::new(),
+        }));
+        let signal = Arc::new(signal);
+        let threads = (0..threads.max(1))
+            .map(|_| {
+                let to_load = to_load.clone();
+                let loaded = loaded.clone();
+                let signal = signal.clone();
+                let source = source.clone();
+                std::thread::spawn(move || Self::work(to_load, loaded, signal, source))
+            })
+            .collect();
+        Self {
+            threads,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> ThreadPoolLoader<S>
+where
+    S: Source<Input = PathBuf>,
+{
+    /// Block until every worker thread has stopped.
+    ///
+    /// The workers stop once every Manager has been dropped or the `LoaderHandle` requested a shutdown.
+    pub fn join(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+    /// The worker loop. Only one idle worker waits on the requests at a time, the others wait for the lock.
+    /// Each worker takes the queued load with the highest priority.
+    fn work(
+        requests: Arc<Mutex<Requests<S>>>,
+        loaded: Vec<Sender<LoadResult<S>>>,
+        signal: Arc<LoaderSignal>,
+        source: Arc<S>,
+    ) {
+        loop {
+            let request = {
+                let mut requests = requests.lock().unwrap();
+                let Requests { to_load, queue } = &mut *requests;
+                block_on(poll_fn(|cx| {
+                    if signal.poll_shutdown(cx).is_ready() {
+                        to_load.close();
+                        while let Poll::Ready(Some(request)) = to_load.poll_next_unpin(cx) {
+                            queue.push(request);
+                        }
+                        for job in queue.drain() {
+                            if let Some(sender) = loaded.get(job.manager) {
+                                let _ = sender.send(job.disconnected());
+                            }
+                        }
+                        return Poll::Ready(None);
+                    }
+                    loop {
+                        match to_load.poll_next_unpin(cx) {
+                            Poll::Ready(Some(request)) => queue.push(request),
+                            Poll::Ready(None) => return Poll::Ready(queue.pop()),
+                            Poll::Pending => break,
+                        }
+                    }
+                    match queue.pop() {
+                        Some(load) => Poll::Ready(Some(load)),
+                        None => Poll::Pending,
+                    }
+                }))
+            };
+            match request {
+                Some(job) => {
+                    let manager_idx = job.manager;
+                    let (_, result) = job.load(&*source);
+                    if let Some(sender) = loaded.get(manager_idx) {
+                        if sender.send(result).is_err() {}
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
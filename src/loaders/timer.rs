@@ -0,0 +1,47 @@
+use futures::task::{Context, Poll, Waker};
+use std::{
+    sync::mpsc::{channel, RecvTimeoutError, Sender},
+    time::Instant,
+};
+
+/// Wakes tasks at an `Instant`. The time is kept by one sleeping thread, so no runtime timer is needed.
+/// The thread stops once the Timer is dropped.
+pub(crate) struct Timer {
+    wake_at: Sender<(Instant, Waker)>,
+}
+
+impl Timer {
+    pub(crate) fn new() -> Self {
+        let (wake_at, requests) = channel::<(Instant, Waker)>();
+        std::thread::spawn(move || {
+            let mut waiting: Vec<(Instant, Waker)> = Vec::new();
+            loop {
+                let request = match waiting.iter().map(|(at, _)| *at).min() {
+                    Some(next) => requests.recv_timeout(next.saturating_duration_since(Instant::now())),
+                    None => requests.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match request {
+                    Ok(request) => waiting.push(request),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                let now = Instant::now();
+                waiting.retain(|(at, waker)| {
+                    if *at <= now {
+                        waker.wake_by_ref();
+                    }
+                    *at > now
+                });
+            }
+        });
+        Self { wake_at }
+    }
+    /// Returns Ready once `at` has passed, otherwise wakes the task at `at`.
+    pub(crate) fn poll_until(&self, at: Instant, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= at {
+            return Poll::Ready(());
+        }
+        let _ = self.wake_at.send((at, cx.waker().clone()));
+        Poll::Pending
+    }
+}
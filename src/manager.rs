@@ -1,11 +1,21 @@
 use crate::{
     asset::{Asset, AssetHandle},
-    loaders::{LoadStatus, Loader},
-    sources::Source,
+    dependency::{Notice, SharedGraph},
+    id::Slots,
+    loaders::{Construct, LoadResult, LoadStatus, Loader, LoaderRequest, Payload, Priority, Request},
+    reference::Tracker,
+    sources::{SharedSource, Source},
+    AssetFuture, AssetId, AssetKey, AssetRef, Dependency, Error, ManagerId, WeakAssetRef,
 };
+use futures::channel::mpsc::UnboundedSender;
+use std::any::Any;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{Receiver, Sender};
-use std::{collections::HashMap, io::ErrorKind, sync::Arc};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
 /// Manages the loading and unloading of one struct that implements the Asset trait.
 /// Regular calls to maintain support lazy loading, auto unload(optional default:off) and auto drop(optional default:off).
@@ -16,14 +26,45 @@ where
 {
     drop: bool,
     unload: bool,
+    budget: Option<usize>,
+    grace: Option<Grace>,
+    maintains: u64,
+    tokens: u64,
+    watch: Option<Duration>,
+    last_watch: Instant,
     loader_id: usize,
-    load_send: Sender<(usize, PathBuf, L::TransferSupplement)>,
-    load_recv: Receiver<(PathBuf, <L::Source as Source>::Output)>,
-    asset_handles: HashMap<PathBuf, AssetHandle<A, L>>,
+    load_send: UnboundedSender<LoaderRequest<L>>,
+    load_recv: Receiver<LoadResult<L::Source>>,
+    /// Results taken from `load_recv` that a budgeted `maintain` left for the next call.
+    backlog: VecDeque<LoadResult<L::Source>>,
+    asset_handles: Slots<A, AssetHandle<A, L>>,
+    ids: HashMap<PathBuf, AssetId<A>>,
+    release_send: Sender<(u32, u32)>,
+    release_recv: Receiver<(u32, u32)>,
+    unreferenced: Vec<AssetId<A>>,
+    lru: VecDeque<AssetId<A>>,
+    expiring: VecDeque<(AssetId<A>, Instant, u64)>,
     loaded_once: Vec<PathBuf>,
-    data: A::ManagerSupplement,
+    failed_once: Vec<PathBuf>,
+    reloaded_once: Vec<PathBuf>,
+    graph: SharedGraph<L::TransferSupplement>,
+    source: SharedSource<L::Source>,
+    insert_dependencies: Option<fn(&Path) -> A::AssetSupplement>,
+    fallback: Option<Arc<A::Structure>>,
+    fallback_tracker: Arc<Tracker>,
+    on_loader: Option<(MakeConstruct<A, L>, Unpack<A, L>)>,
+    data: Arc<A::ManagerSupplement>,
 }
 
+/// Creates the `Construct` sent to the Loader from the supplements and the path of an Asset.
+type MakeConstruct<A, L> = fn(
+    &Arc<<A as Asset<L>>::ManagerSupplement>,
+    &<A as Asset<L>>::AssetSupplement,
+    &Path,
+) -> Construct<<<L as Loader>::Source as Source>::Output>;
+/// Takes the Asset out of a `Payload::Constructed`.
+type Unpack<A, L> = fn(Box<dyn Any + Send>) -> Option<<A as Asset<L>>::Structure>;
+
 unsafe impl<A, L> Sync for Manager<A, L>
 where
     A: Asset<L>,
@@ -42,19 +83,43 @@ where
     /// capacity until `insert` is called.
     pub(crate) fn new(
         loader_id: usize,
-        load_send: Sender<(usize, PathBuf, L::TransferSupplement)>,
-        load_recv: Receiver<(PathBuf, <L::Source as Source>::Output)>,
+        load_send: UnboundedSender<LoaderRequest<L>>,
+        load_recv: Receiver<LoadResult<L::Source>>,
+        graph: SharedGraph<L::TransferSupplement>,
+        source: SharedSource<L::Source>,
         data: A::ManagerSupplement,
     ) -> Self {
+        let (release_send, release_recv) = channel();
         Self {
             drop: false,
             unload: false,
+            budget: None,
+            grace: None,
+            maintains: 0,
+            tokens: 0,
+            watch: None,
+            last_watch: Instant::now(),
             loader_id,
             load_send,
             load_recv,
-            asset_handles: HashMap::new(),
+            backlog: VecDeque::new(),
+            asset_handles: Slots::new(),
+            ids: HashMap::new(),
+            release_send,
+            release_recv,
+            unreferenced: Vec::new(),
+            lru: VecDeque::new(),
+            expiring: VecDeque::new(),
             loaded_once: Vec::new(),
-            data,
+            failed_once: Vec::new(),
+            reloaded_once: Vec::new(),
+            graph,
+            source,
+            insert_dependencies: None,
+            fallback: None,
+            fallback_tracker: Tracker::detached(),
+            on_loader: None,
+            data: Arc::new(data),
         }
     }
 
@@ -75,88 +140,340 @@ where
     /// Set the `auto_unload` of the Manager to `true`.
     ///
     /// The Manager will drop its reference to the Asset on the next call of its `maintain` function
-    /// if its strong_refcount is equal to 1.
+    /// if no `AssetRef` to it is alive. Only Assets that just finished loading or whose last `AssetRef`
+    /// has been dropped are checked, so unloading does not scan every Asset.
     ///
     pub fn auto_unload(mut self) -> Self {
         self.unload = true;
         self
     }
+    /// Set the `auto_unload` of the Manager to `true`, but keep unreferenced Assets loaded for `ttl`.
+    ///
+    /// An Asset is unloaded during the first `maintain` at least `ttl` after its last `AssetRef` was dropped,
+    /// unless it is referenced again in the meantime.
+    ///
+    pub fn unload_after(mut self, ttl: Duration) -> Self {
+        self.unload = true;
+        self.grace = Some(Grace::Time(ttl));
+        self
+    }
+    /// Set the `auto_unload` of the Manager to `true`, but keep unreferenced Assets loaded for `n` calls of `maintain`.
+    ///
+    /// An Asset is unloaded during the `n`th `maintain` after the one that noticed its last `AssetRef` was dropped,
+    /// unless it is referenced again in the meantime.
+    ///
+    pub fn unload_after_maintains(mut self, n: u64) -> Self {
+        self.unload = true;
+        self.grace = Some(Grace::Maintains(n));
+        self
+    }
+    /// Keep unreferenced Assets loaded as long as they fit into `budget` bytes.
+    ///
+    /// The size of an Asset is reported by `Asset::size`. When the unreferenced Assets exceed the budget,
+    /// `maintain` unloads the ones whose last `AssetRef` was dropped the longest time ago until they fit again.
+    /// Assets with an alive `AssetRef` are never unloaded. `auto_unload` takes precedence over the cache.
+    ///
+    pub fn cache(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+    /// Enable hot reloading for the Manager.
+    ///
+    /// Every `interval` the Manager checks the modification time of all loaded Assets during `maintain`,
+    /// as reported by `Source::modified` of the Loader.
+    /// Changed files are loaded again and swapped in once they are constructed; the old Asset stays
+    /// available until then. Reloaded paths are reported by `get_reloaded_once`.
+    ///
+    pub fn watch(mut self, interval: Duration) -> Self {
+        self.watch = Some(interval);
+        self
+    }
+    /// Set the fallback Asset returned by `get_or_fallback` for Assets that are not loaded
+    /// and have no fallback of their own.
+    ///
+    pub fn fallback(mut self, asset: A::Structure) -> Self {
+        self.fallback = Some(Arc::new(asset));
+        self
+    }
+    /// Construct the Assets of the Manager on the Loader instead of during `maintain`,
+    /// so `maintain` only has to swap in constructed Assets.
+    ///
+    /// Assets with dependencies are still constructed during `maintain` once their dependencies are loaded.
+    ///
+    pub fn construct_on_loader(mut self) -> Self
+    where
+        A: 'static,
+        L: 'static,
+        A::Structure: Send + 'static,
+        A::AssetSupplement: Clone + Send + 'static,
+        A::ManagerSupplement: Send + Sync + 'static,
+    {
+        let make: MakeConstruct<A, L> = |data_mgr, data_ass, path| {
+            let data_mgr = data_mgr.clone();
+            let data_ass = data_ass.clone();
+            let path = path.to_path_buf();
+            Box::new(move |b| {
+                if !A::dependencies(&b, &data_ass, &data_mgr).is_empty() {
+                    return Ok(Payload::Raw(b));
+                }
+                match A::construct(b, &data_ass, &data_mgr) {
+                    Ok(a) => Ok(Payload::Constructed(Box::new(a))),
+                    Err(e) => Err(Error::decode_failed(path, e)),
+                }
+            })
+        };
+        let unpack: Unpack<A, L> = |a| a.downcast().ok().map(|a| *a);
+        self.on_loader = Some((make, unpack));
+        self
+    }
+    /// Accept dependencies on paths unknown to the Manager.
+    ///
+    /// When another Asset depends on a path that has not been inserted, the path is inserted
+    /// with the AssetSupplement returned by `data`. Otherwise such dependencies fail with `Error::NotRegistered`.
+    ///
+    pub fn insert_dependencies(mut self, data: fn(&Path) -> A::AssetSupplement) -> Self {
+        self.insert_dependencies = Some(data);
+        self
+    }
+    /// Returns the `ManagerId` used by other Assets to declare dependencies on Assets of this Manager.
+    pub fn manager_id(&self) -> ManagerId {
+        ManagerId(self.loader_id)
+    }
     /// Insert an Assets Path into the Manager and return its key without loading the asset.
     /// If the specified path is already known to the Manager it will return the known paths key.
     ///
     /// If auto_dropout is activated the Asset has to be explicitly loaded with the given key after inserting
     /// or it will be dropped in the next call to maintain.
     ///
-    pub fn insert<P: AsRef<Path>>(&mut self, path: P, data: A::AssetSupplement) {
+    pub fn insert<P: AsRef<Path>>(&mut self, path: P, data: A::AssetSupplement) -> AssetId<A> {
+        if let Some(id) = self.ids.get(path.as_ref()) {
+            return *id;
+        }
         let path: PathBuf = path.as_ref().into();
-        self.asset_handles
-            .entry(path.clone())
-            .or_insert(AssetHandle::new(path, data));
+        let id = self.asset_handles.insert(AssetHandle::new(path.clone(), data));
+        self.ids.insert(path, id);
+        id
     }
     /// Insert an Assets Path and the loaded Asset into the Manager and return its key.
-    /// If the specified path is already known to the Manager it will return the known paths key.
+    /// If the specified path is already known to the Manager the Asset and its supplement are replaced and the known paths key is returned.
     ///
     /// If auto_dropout is activated the Asset has to be explicitly loaded with the given key after inserting
     /// or it will be dropped in the next call to maintain.
+    /// A running load of the path is cancelled so it can not overwrite the inserted Asset.
     ///
-    pub fn insert_raw<P: AsRef<Path>>(&mut self, path: P, asset: A::Structure, data: A::AssetSupplement) {
-        let path: PathBuf = path.as_ref().into();
-        let mut handle = AssetHandle::new(path.clone(), data);
-        handle.set(asset);
-        self.asset_handles.insert(path, handle);
+    pub fn insert_raw<P: AsRef<Path>>(&mut self, path: P, asset: A::Structure, data: A::AssetSupplement) -> AssetId<A> {
+        let id = match self.ids.get(path.as_ref()) {
+            Some(id) => {
+                let id = *id;
+                if let Some(handle) = self.asset_handles.get_mut(id) {
+                    handle.data = data;
+                }
+                id
+            }
+            None => self.insert(path, data),
+        };
+        self.cancel(id);
+        let tracker = Tracker::new(id.to_raw(), self.release_send.clone());
+        if let Some(handle) = self.asset_handles.get_mut(id) {
+            let dependency = Dependency::new(ManagerId(self.loader_id), &handle.path);
+            let replaced = handle.status.eq(&LoadStatus::Loaded);
+            handle.set(asset, tracker);
+            let mut graph = self.graph.lock().unwrap();
+            if replaced {
+                graph.reloaded(&dependency);
+            } else {
+                graph.loaded(&dependency, Ok(()));
+            }
+            if self.unload || self.budget.is_some() {
+                self.unreferenced.push(id);
+            }
+        }
+        id
+    }
+    /// Insert an Assets Path into the Manager like `insert` and pin it.
+    pub fn insert_pinned<P: AsRef<Path>>(&mut self, path: P, data: A::AssetSupplement) -> AssetId<A> {
+        let id = self.insert(path, data);
+        self.pin(id);
+        id
     }
-    /// Loads an unloaded Asset known to the the Manager and returns its Arc<T>.
-    /// If the asset is already loaded it will just return the Asset.
+    /// Pins an Asset known to the the Manager. Pinned Assets are never unloaded or dropped by `maintain`,
+    /// regardless of `auto_unload`, `auto_dropout` or the cache. They can still be unloaded and dropped explicitly.
     ///
-    /// If there is no valid file found at the specified path it will return an io::Error.
-    /// If the key is not found it will return None.
+    /// If the key is not found it will do nothing.
+    ///
+    pub fn pin<K: AssetKey<A>>(&mut self, key: K) {
+        if let Ok(handle) = self.handle_mut(key) {
+            handle.pinned = true;
+        }
+    }
+    /// Unpins an Asset known to the the Manager. If it is loaded and unreferenced, the next `maintain`
+    /// treats it like an Asset whose last `AssetRef` was just dropped.
+    ///
+    /// If the key is not found it will do nothing.
+    ///
+    pub fn unpin<K: AssetKey<A>>(&mut self, key: K) {
+        if let Ok(id) = self.id_of(key) {
+            let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+            handle.pinned = false;
+            if handle.status.eq(&LoadStatus::Loaded)
+                && handle.references() == 0
+                && (self.unload || self.budget.is_some())
+            {
+                self.unreferenced.push(id);
+            }
+        }
+    }
+    /// Returns true if an Asset known to the the Manager is pinned.
+    pub fn is_pinned<K: AssetKey<A>>(&self, key: K) -> bool {
+        self.handle(key).is_ok_and(|h| h.pinned)
+    }
+    /// Loads an unloaded Asset known to the the Manager.
+    /// The Asset can be fetched with `get` once `maintain` received it from the Loader.
+    ///
+    /// If the key is not found it will return `Error::NotRegistered` or `Error::StaleId`.
+    /// The path and `supp` are passed to the Source of the Loader, if it cannot load them the Asset fails
+    /// with `Error::FileNotFound` or `Error::SourceFailed`.
     ///
-    pub fn load<P: AsRef<Path>>(&mut self, path: P, supp: L::TransferSupplement) -> Result<(), std::io::Error> {
-        let mut a = self
-            .asset_handles
-            .get_mut(path.as_ref())
-            .ok_or(std::io::Error::new(
-                ErrorKind::NotFound,
-                format!("Entry not found! {:?}", path.as_ref()),
-            ))?;
-        if !path.as_ref().exists() {
-            Err(std::io::Error::new(
-                ErrorKind::NotFound,
-                format!("File not found! {:?}", path.as_ref()),
-            ))
-        } else if a.status.eq(&LoadStatus::Loading){
-            Err(std::io::Error::new(
-                ErrorKind::AlreadyExists,
-                format!("Image already loading! {:?}", path.as_ref()),
-            ))
+    pub fn load<K: AssetKey<A>>(&mut self, key: K, supp: L::TransferSupplement) -> Result<(), Error> {
+        self.load_with_priority(key, supp, 0)
+    }
+    /// Loads an unloaded Asset known to the the Manager like `load`.
+    /// The Loader starts loads with a higher `priority` first, `load` uses a priority of 0.
+    ///
+    pub fn load_with_priority<K: AssetKey<A>>(
+        &mut self,
+        key: K,
+        supp: L::TransferSupplement,
+        priority: Priority,
+    ) -> Result<(), Error> {
+        let id = key.resolve(&self.ids)?;
+        let a = self.asset_handles.get_mut(id).ok_or(Error::StaleId)?;
+        if a.status.eq(&LoadStatus::Loading){
+            Err(Error::AlreadyLoading(a.path.clone()))
         } else {
-            a.status = LoadStatus::Loading;
-            let package = (self.loader_id, path.as_ref().into(), supp);
+            if self.watch.is_some() {
+                a.modified = modified(&self.source, &a.path);
+            }
+            a.supp = Some(supp.clone());
+            self.tokens += 1;
+            let package = Request::Load {
+                manager: self.loader_id,
+                path: a.path.clone(),
+                supp,
+                priority,
+                token: self.tokens,
+                construct: match self.on_loader {
+                    Some((make, _)) => Some(make(&self.data, &a.data, &a.path)),
+                    None => None,
+                },
+            };
             self
                 .load_send
-                .send(package)
-                .map_err(|e| std::io::Error::new(
-                    ErrorKind::ConnectionReset,
-                    format!("Error sending! {:?}", e),
-                ))
+                .unbounded_send(package)
+                .map_err(|_| Error::LoaderDisconnected(a.path.clone()))?;
+            a.status = LoadStatus::Loading;
+            a.reloading = false;
+            a.token = self.tokens;
+            Ok(())
+        }
+    }
+    /// Changes the priority of an Asset known to the the Manager that is loading.
+    /// Has no effect once the Loader started loading it.
+    ///
+    /// If the key is not found or the Asset is not loading it will do nothing.
+    ///
+    pub fn set_priority<K: AssetKey<A>>(&mut self, key: K, priority: Priority) {
+        if let Ok(handle) = self.handle(key) {
+            if handle.status.eq(&LoadStatus::Loading) {
+                let _ = self.load_send.unbounded_send(Request::Reprioritize {
+                    manager: self.loader_id,
+                    path: handle.path.clone(),
+                    priority,
+                });
+            }
+        }
+    }
+    /// Loads an Asset known to the the Manager like `load` and returns a future resolving to the constructed Asset.
+    /// If the Asset is already loaded the future is ready immediately,
+    /// if it is already loading the future waits for the running load.
+    ///
+    /// The Asset is constructed during `maintain`, so the Manager has to be maintained for the future to complete.
+    /// If the Asset is unloaded or dropped before it finished loading the future returns `Error::Cancelled`.
+    ///
+    pub fn load_async<K: AssetKey<A>>(&mut self, key: K, supp: L::TransferSupplement) -> AssetFuture<A::Structure> {
+        let id = match self.id_of(key) {
+            Ok(id) => id,
+            Err(e) => return AssetFuture::ready(Err(e)),
+        };
+        let handle = self.asset_handles.get(id).expect("id was just resolved");
+        if let Some(a) = handle.get() {
+            return AssetFuture::ready(Ok(a));
+        }
+        if handle.status.ne(&LoadStatus::Loading) {
+            if let Err(e) = self.load(id, supp) {
+                return AssetFuture::ready(Err(e));
+            }
         }
+        let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+        let (future, s) = AssetFuture::pending(handle.path.clone());
+        handle.wait(s);
+        future
     }
     /// Unloads an Asset known to the the Manager. The Asset can be reloaded with the same key.
     ///
-    /// The Arc of the Asset will be dropped. The Asset may still be used but the Manager wont know about it anymore.
+    /// The Arc of the Asset will be dropped. Existing `AssetRef`s may still be used but the Manager wont know about them anymore.
     /// If the key is not found it will do nothing.
     ///
-    pub fn unload<P: AsRef<Path>>(&mut self, path: P) {
-        if let Some(handle) = self.asset_handles.get_mut(path.as_ref()) {
-            handle.unload()
+    pub fn unload<K: AssetKey<A>>(&mut self, key: K) {
+        if let Ok(id) = self.id_of(key) {
+            self.cancel(id);
+            let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+            handle.unload();
+            let dependent = Dependency::new(ManagerId(self.loader_id), &handle.path);
+            self.graph.lock().unwrap().remove(&dependent);
+        }
+    }
+    /// Cancels loading an Asset known to the the Manager.
+    ///
+    /// Queued requests are removed from the Loader and the results of loads that already started are ignored.
+    /// A loading Asset returns to `LoadStatus::NotLoaded` and its futures return `Error::Cancelled`,
+    /// an Asset that is being reloaded keeps its current version.
+    /// If the key is not found or the Asset is not loading it will do nothing.
+    ///
+    pub fn cancel<K: AssetKey<A>>(&mut self, key: K) {
+        if let Ok(id) = self.id_of(key) {
+            let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+            if handle.busy() {
+                let _ = self.load_send.unbounded_send(Request::Cancel {
+                    manager: self.loader_id,
+                    path: handle.path.clone(),
+                });
+                handle.cancel();
+            }
         }
     }
-    /// Drops an Asset known to the the Manager. The key may be reused by another Asset.
+    /// Drops an Asset known to the the Manager. The path may be reused by another Asset,
+    /// but the `AssetId` of the dropped Asset stays invalid. A running load is cancelled like with `cancel`.
     ///
     /// If the key is not found it will do nothing.
     ///
-    pub fn drop<P: AsRef<Path>>(&mut self, path: P) {
-        self.asset_handles.remove(path.as_ref());
+    pub fn drop<K: AssetKey<A>>(&mut self, key: K) {
+        if let Ok(id) = key.resolve(&self.ids) {
+            self.cancel(id);
+            if let Some(handle) = self.asset_handles.remove(id) {
+                self.ids.remove(&handle.path);
+                let dependent = Dependency::new(ManagerId(self.loader_id), &handle.path);
+                self.graph.lock().unwrap().remove(&dependent);
+            }
+        }
+    }
+    /// Returns the `AssetId` of a path known to the Manager.
+    pub fn id<P: AsRef<Path>>(&self, path: P) -> Option<AssetId<A>> {
+        self.ids.get(path.as_ref()).copied()
+    }
+    /// Returns the path of an Asset known to the Manager.
+    pub fn path<K: AssetKey<A>>(&self, key: K) -> Option<&Path> {
+        Some(&self.handle(key).ok()?.path)
     }
     /// Returns an Asset known to the the Manager.
     ///
@@ -164,34 +481,98 @@ where
     /// If the Asset is not loaded it will return None.
     /// Call status() to get detailed information.
     ///
-    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<Arc<A::Structure>> {
-        Some(self.asset_handles.get(path.as_ref())?.get()?.clone())
+    pub fn get<K: AssetKey<A>>(&self, key: K) -> Option<AssetRef<A::Structure>> {
+        self.handle(key).ok()?.get()
+    }
+    /// Returns an Asset known to the the Manager together with its version.
+    ///
+    /// If the key is not found or the Asset is not loaded it will return None.
+    ///
+    pub fn get_with_version<K: AssetKey<A>>(&self, key: K) -> Option<(AssetRef<A::Structure>, u64)> {
+        let handle = self.handle(key).ok()?;
+        Some((handle.get()?, handle.version))
+    }
+    /// Returns the version of an Asset known to the the Manager.
+    ///
+    /// The version starts at 0 and increases every time a new Asset is stored for the key:
+    /// when it is loaded, reloaded or replaced with `insert_raw`. Data derived from an Asset is stale
+    /// once the version changed. If the key is not found it will return None.
+    ///
+    pub fn version<K: AssetKey<A>>(&self, key: K) -> Option<u64> {
+        Some(self.handle(key).ok()?.version)
+    }
+    /// Returns an Asset known to the the Manager, or a fallback Asset while it is loading, failed or unloaded.
+    /// The flag is true if the returned Asset is the fallback.
+    ///
+    /// The fallback set for the key with `set_fallback` is preferred over the fallback of the Manager.
+    /// If the key is not found or there is no fallback for it, it will return None.
+    ///
+    pub fn get_or_fallback<K: AssetKey<A>>(&self, key: K) -> Option<(AssetRef<A::Structure>, bool)> {
+        let handle = self.handle(key).ok()?;
+        if let Some(a) = handle.get() {
+            return Some((a, false));
+        }
+        let fallback = handle.fallback.as_ref().or(self.fallback.as_ref())?;
+        Some((AssetRef::new(fallback.clone(), self.fallback_tracker.clone()), true))
+    }
+    /// Set the fallback Asset returned by `get_or_fallback` for an Asset known to the the Manager.
+    ///
+    /// If the key is not found it will do nothing.
+    ///
+    pub fn set_fallback<K: AssetKey<A>>(&mut self, key: K, asset: A::Structure) {
+        if let Ok(handle) = self.handle_mut(key) {
+            handle.fallback = Some(Arc::new(asset));
+        }
+    }
+    /// Returns a `WeakAssetRef` to an Asset known to the the Manager, which does not keep the Asset loaded.
+    ///
+    /// If the key is not found or the Asset is not loaded it will return None.
+    ///
+    pub fn get_weak<K: AssetKey<A>>(&self, key: K) -> Option<WeakAssetRef<A::Structure>> {
+        Some(AssetRef::downgrade(&self.get(key)?))
     }
     /// Returns an Asset known to the the Manager.
     ///
     /// If the key is not found it will return None.
     /// If the Asset is not loading it will return None.
     /// Will wait for the Asset to become available on the receiver and then returning it.
+    /// Results for other Assets arriving in the meantime are stored in their own handles.
+    /// If the Asset waits for dependencies held by another Manager it will return None,
+    /// as they are only loaded during the `maintain` of that Manager.
     ///
-    pub fn get_blocking<P: AsRef<Path>>(&mut self, path: P) -> Option<Arc<A::Structure>> {
-        match self.asset_handles.get(path.as_ref())?.get() {
-            None => {
-                if let Some(handle) = self.asset_handles.get_mut(path.as_ref()) {
-                    if handle.status.eq(&LoadStatus::Loading) {
-                        while let Ok((p, out)) = self.load_recv.recv() {
-                            if let Ok(a) = A::construct(out, &handle.data, &self.data) {
-                                handle.set(a);
-                                self.loaded_once.push(path.as_ref().into());
-                                if p.eq(path.as_ref()) {
-                                    return Some(handle.get()?.clone());
-                                }
-                            }
-                        }
-                    }
-                }
-                None
+    pub fn get_blocking<K: AssetKey<A>>(&mut self, key: K) -> Option<AssetRef<A::Structure>> {
+        self.get_blocking_until(key, None)
+    }
+    /// Returns an Asset known to the the Manager like `get_blocking`,
+    /// but gives up and returns None if the Asset is not available within `timeout`.
+    ///
+    pub fn get_blocking_timeout<K: AssetKey<A>>(&mut self, key: K, timeout: Duration) -> Option<AssetRef<A::Structure>> {
+        self.get_blocking_until(key, Some(Instant::now() + timeout))
+    }
+    fn get_blocking_until<K: AssetKey<A>>(&mut self, key: K, deadline: Option<Instant>) -> Option<AssetRef<A::Structure>> {
+        let id = self.id_of(key).ok()?;
+        loop {
+            let handle = self.asset_handles.get(id)?;
+            if let Some(a) = handle.get() {
+                return Some(a);
+            }
+            if handle.status.ne(&LoadStatus::Loading) {
+                return None;
+            }
+            // only the maintain of another Manager can load its dependencies
+            if handle.pending.is_some() && handle.dependencies.iter().any(|d| d.manager() != self.manager_id()) {
+                return None;
             }
-            Some(a) => Some(a.clone()),
+            let result = match (self.backlog.pop_front(), deadline) {
+                (Some(result), _) => result,
+                (None, None) => self.load_recv.recv().ok()?,
+                (None, Some(deadline)) => self
+                    .load_recv
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    .ok()?,
+            };
+            self.receive(result);
+            self.resolve_dependencies();
         }
     }
     /// Returns loaded assets once as soon as they have the LoadStatus::Loaded.
@@ -202,68 +583,420 @@ where
         }
         list
     }
+    /// Returns failed assets once as soon as they have the LoadStatus::Failed.
+    /// Call error() to get the reason.
+    pub fn get_failed_once(&mut self) -> Vec<PathBuf> {
+        let mut list = Vec::new();
+        if !self.failed_once.is_empty() {
+            std::mem::swap(&mut list, &mut self.failed_once);
+        }
+        list
+    }
+    /// Returns hot reloaded assets once as soon as their new version has been swapped in.
+    /// Reloads that failed are reported by get_failed_once, the previous Asset stays loaded.
+    pub fn get_reloaded_once(&mut self) -> Vec<PathBuf> {
+        let mut list = Vec::new();
+        if !self.reloaded_once.is_empty() {
+            std::mem::swap(&mut list, &mut self.reloaded_once);
+        }
+        list
+    }
     /// Returns the LoadStatus of an Asset known to the the Manager.
     ///
     /// If the key is not found it will return None.
     ///
-    pub fn status<P: AsRef<Path>>(&self, path: P) -> Option<LoadStatus> {
-        Some(self.asset_handles.get(path.as_ref())?.status)
+    pub fn status<K: AssetKey<A>>(&self, key: K) -> Option<LoadStatus> {
+        Some(self.handle(key).ok()?.status)
+    }
+    /// Returns the Error that caused an Asset known to the the Manager to fail loading.
+    ///
+    /// If the key is not found or the Asset has not failed it will return None.
+    /// The Asset can be retried by calling `load` again.
+    ///
+    pub fn error<K: AssetKey<A>>(&self, key: K) -> Option<&Error> {
+        self.handle(key).ok()?.error()
     }
-    pub fn data_asset<P: AsRef<Path>>(&self, path: P) -> Option<&A::AssetSupplement>{
-        Some(&self.asset_handles.get(path.as_ref())?.data)
+    pub fn data_asset<K: AssetKey<A>>(&self, key: K) -> Option<&A::AssetSupplement>{
+        Some(&self.handle(key).ok()?.data)
     }
     pub fn data_manager<P: AsRef<Path>>(&self) -> Option<&A::ManagerSupplement>{
-        Some(&self.data)
+        Some(&*self.data)
     }
     /// Maintains the manager. Needs to be called for lazy loading, to unload unused Assets and maybe even drop them.
     /// The default Manager will not drop or unload any Assets. So maintain will just load Assets.
     /// Will be slow if used with a large initial capacity + min_drop + min_unload as it will iterate over every Asset.
     ///
     pub fn maintain(&mut self) {
-        if self.unload {
-            self.asset_handles
-                .values_mut()
-                .filter(|h| h.status.eq(&LoadStatus::Loaded))
-                .filter(|h| Arc::strong_count(h.get().unwrap()).eq(&1))
-                .for_each(|h| h.unload());
+        self.maintain_limited(None, usize::MAX);
+    }
+    /// Maintains the manager like `maintain`, but stops handling finished loads once `budget` is spent.
+    /// At least one finished load is handled per call.
+    ///
+    /// Returns the number of finished loads left for the next call.
+    ///
+    pub fn maintain_with_budget(&mut self, budget: Duration) -> usize {
+        self.maintain_limited(Some(Instant::now() + budget), usize::MAX)
+    }
+    /// Maintains the manager like `maintain`, but handles at most `max` finished loads.
+    ///
+    /// Returns the number of finished loads left for the next call.
+    ///
+    pub fn maintain_max_items(&mut self, max: usize) -> usize {
+        self.maintain_limited(None, max)
+    }
+    fn maintain_limited(&mut self, deadline: Option<Instant>, max: usize) -> usize {
+        self.maintains += 1;
+        let released = self.release_recv.try_iter().map(AssetId::from_raw);
+        if let (true, Some(grace)) = (self.unload, self.grace) {
+            self.unreferenced.extend(released);
+            self.expire(grace);
+        } else if self.unload {
+            self.unreferenced.extend(released);
+            for id in self.unreferenced.drain(..) {
+                if let Some(h) = self.asset_handles.get_mut(id) {
+                    if h.status.eq(&LoadStatus::Loaded) && h.references() == 0 && !h.pinned {
+                        h.unload();
+                    }
+                }
+            }
+        } else if let Some(budget) = self.budget {
+            self.unreferenced.extend(released);
+            if !self.unreferenced.is_empty() {
+                self.evict(budget);
+            }
+        } else {
+            released.for_each(std::mem::drop);
         }
         if self.drop {
-            let mut paths_to_drop = Vec::new();
-            for (path, handle) in self.asset_handles.iter() {
-                if self.drop && handle.status != LoadStatus::Loading {
-                    paths_to_drop.push(path.clone());
+            let mut ids_to_drop = Vec::new();
+            for (id, handle) in self.asset_handles.iter() {
+                if self.drop && handle.status != LoadStatus::Loading && !handle.pinned {
+                    ids_to_drop.push(id);
+                }
+            }
+            for id in ids_to_drop {
+                self.drop(id);
+            }
+        }
+        if let Some(interval) = self.watch {
+            if self.last_watch.elapsed() >= interval {
+                self.last_watch = Instant::now();
+                self.reload_modified();
+            }
+        }
+        let mut handled = 0;
+        loop {
+            self.backlog.extend(self.load_recv.try_iter());
+            while !self.backlog.is_empty() {
+                let spent = deadline.is_some_and(|d| handled > 0 && Instant::now() >= d);
+                if handled >= max || spent {
+                    return self.backlog.len();
+                }
+                if let Some(result) = self.backlog.pop_front() {
+                    self.receive(result);
+                }
+                handled += 1;
+            }
+            if !self.resolve_dependencies() {
+                return 0;
+            }
+        }
+    }
+    /// Queue newly unreferenced Assets and unload the queued ones whose grace period is over.
+    fn expire(&mut self, grace: Grace) {
+        let now = Instant::now();
+        for id in self.unreferenced.drain(..) {
+            if let Some(h) = self.asset_handles.get_mut(id) {
+                h.unreferenced_since = Some((now, self.maintains));
+                self.expiring.push_back((id, now, self.maintains));
+            }
+        }
+        while let Some(&(id, since, tick)) = self.expiring.front() {
+            let expired = match grace {
+                Grace::Time(ttl) => now.duration_since(since) >= ttl,
+                Grace::Maintains(n) => self.maintains - tick >= n,
+            };
+            if !expired {
+                break;
+            }
+            self.expiring.pop_front();
+            if let Some(h) = self.asset_handles.get_mut(id) {
+                // Only the latest queue entry of an Asset counts, it may have been released again since.
+                if h.unreferenced_since.eq(&Some((since, tick)))
+                    && h.status.eq(&LoadStatus::Loaded)
+                    && h.references() == 0
+                    && !h.pinned
+                {
+                    h.unload();
+                }
+            }
+        }
+    }
+    /// Move newly unreferenced Assets to the back of the LRU list and unload Assets from its front
+    /// until the unreferenced Assets fit into the budget.
+    fn evict(&mut self, budget: usize) {
+        for id in self.unreferenced.drain(..) {
+            self.lru.retain(|i| i.ne(&id));
+            self.lru.push_back(id);
+        }
+        let handles = &self.asset_handles;
+        self.lru.retain(|id| {
+            handles
+                .get(*id)
+                .is_some_and(|h| h.status.eq(&LoadStatus::Loaded) && h.references() == 0 && !h.pinned)
+        });
+        let mut size: usize = self.lru.iter().filter_map(|id| handles.get(*id)).map(|h| h.size).sum();
+        while size > budget {
+            let id = match self.lru.pop_front() {
+                Some(id) => id,
+                None => break,
+            };
+            if let Some(h) = self.asset_handles.get_mut(id) {
+                size -= h.size;
+                h.unload();
+            }
+        }
+    }
+    /// Request a reload of every loaded Asset whose file changed since it was loaded.
+    fn reload_modified(&mut self) {
+        for handle in self.asset_handles.values_mut() {
+            if handle.status.ne(&LoadStatus::Loaded) || handle.reloading {
+                continue;
+            }
+            let (known, supp) = match (handle.modified, &handle.supp) {
+                (Some(known), Some(supp)) => (known, supp.clone()),
+                _ => continue,
+            };
+            let current = modified(&self.source, &handle.path);
+            if current.map_or(true, |m| m.eq(&known)) {
+                continue;
+            }
+            self.tokens += 1;
+            let request = Request::Load {
+                manager: self.loader_id,
+                path: handle.path.clone(),
+                supp,
+                priority: 0,
+                token: self.tokens,
+                construct: match self.on_loader {
+                    Some((make, _)) => Some(make(&self.data, &handle.data, &handle.path)),
+                    None => None,
+                },
+            };
+            if self.load_send.unbounded_send(request).is_ok() {
+                handle.token = self.tokens;
+                handle.modified = current;
+                handle.reloading = true;
+            }
+        }
+    }
+    /// Hand a result received from the Loader to the handle of its path.
+    /// Data with dependencies is kept until they are loaded.
+    fn receive(&mut self, (p, token, res): LoadResult<L::Source>) {
+        let id = match self.ids.get(&p) {
+            Some(id) => *id,
+            None => return,
+        };
+        let handle = match self.asset_handles.get_mut(id) {
+            Some(handle) if handle.token == token => handle,
+            _ => return,
+        };
+        handle.token = 0;
+        if let Ok(Payload::Raw(b)) = &res {
+            let dependencies = A::dependencies(b, &handle.data, &self.data);
+            if !dependencies.is_empty() {
+                let dependent = Dependency::new(ManagerId(self.loader_id), &p);
+                let requested = self.graph.lock().unwrap().request(&dependent, &dependencies);
+                match requested {
+                    Ok(()) => {
+                        if let Ok(Payload::Raw(b)) = res {
+                            handle.pending = Some(b);
+                        }
+                        handle.dependencies = dependencies.into_iter().map(|(d, _)| d).collect();
+                        return;
+                    }
+                    Err(e) => return self.finish(id, Err(Error::DependencyFailed(p, Box::new(e)))),
+                }
+            }
+        }
+        self.finish(id, res)
+    }
+    /// Construct loaded data into the handle of `id` and notify the Assets depending on it.
+    fn finish(&mut self, id: AssetId<A>, res: Result<Payload<<L::Source as Source>::Output>, Error>) {
+        let handle = match self.asset_handles.get_mut(id) {
+            Some(handle) => handle,
+            None => return,
+        };
+        let p = handle.path.clone();
+        let reload = handle.reloading && handle.status.eq(&LoadStatus::Loaded);
+        let data = &self.data;
+        let unpack = self.on_loader.map(|(_, unpack)| unpack);
+        let res = res.and_then(|payload| match payload {
+            Payload::Raw(b) => A::construct(b, &handle.data, data).map_err(|e| Error::decode_failed(p.clone(), e)),
+            Payload::Constructed(a) => unpack
+                .and_then(|unpack| unpack(a))
+                .ok_or_else(|| Error::decode_failed(p.clone(), "the Loader constructed another type".into())),
+        });
+        let dependency = Dependency::new(ManagerId(self.loader_id), &p);
+        let mut graph = self.graph.lock().unwrap();
+        match res {
+            Ok(a) if reload => {
+                handle.replace(a);
+                graph.reloaded(&dependency);
+                self.reloaded_once.push(p);
+            }
+            Err(e) if reload => {
+                handle.reload_failed(e);
+                self.failed_once.push(p);
+            }
+            Ok(a) => {
+                handle.set(a, Tracker::new(id.to_raw(), self.release_send.clone()));
+                graph.loaded(&dependency, Ok(()));
+                if self.unload || self.budget.is_some() {
+                    self.unreferenced.push(id);
                 }
+                self.loaded_once.push(p);
             }
-            for path in paths_to_drop {
-                self.drop(path);
+            Err(e) => {
+                graph.loaded(&dependency, Err(&e));
+                handle.fail(e);
+                self.failed_once.push(p);
             }
         }
-        for (p, b) in self.load_recv.try_iter() {
-            if let Some(handle) = self.asset_handles.get_mut(p.as_path()) {
-                if let Ok(a) = A::construct(b, &handle.data, &self.data) {
-                    handle.set(a);
-                    self.loaded_once.push(p);
+    }
+    /// Handle the dependency requests and notices other Managers sent to this Manager.
+    /// Returns false if there were none.
+    fn resolve_dependencies(&mut self) -> bool {
+        let (requests, notices) = self.graph.lock().unwrap().take(self.manager_id());
+        if requests.is_empty() && notices.is_empty() {
+            return false;
+        }
+        for (path, supp, dependent) in requests {
+            self.serve(path, supp, dependent);
+        }
+        for notice in notices {
+            self.notice(notice);
+        }
+        true
+    }
+    /// Load a dependency of `dependent`, or answer right away if it is already loaded or cannot be loaded.
+    fn serve(&mut self, path: PathBuf, supp: L::TransferSupplement, dependent: Dependency) {
+        let dependency = Dependency::new(self.manager_id(), &path);
+        let id = match (self.ids.get(&path), self.insert_dependencies) {
+            (Some(id), _) => *id,
+            (None, Some(data)) => self.insert(&path, data(&path)),
+            (None, None) => {
+                let e = Error::NotRegistered(path);
+                return self.graph.lock().unwrap().answer(dependent, dependency, Err(e));
+            }
+        };
+        let handle = self.asset_handles.get(id).expect("id was just resolved");
+        let result = match handle.status {
+            LoadStatus::Loaded => Ok(()),
+            LoadStatus::Loading => return,
+            LoadStatus::NotLoaded | LoadStatus::Failed => {
+                match self.load(id, supp) {
+                    Ok(()) => return,
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        self.graph.lock().unwrap().answer(dependent, dependency, result);
+    }
+    fn notice(&mut self, notice: Notice) {
+        match notice {
+            Notice::Ready(path, dependency) => {
+                if let Ok(id) = self.id_of(&path) {
+                    let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+                    handle.dependencies.retain(|d| d.ne(&dependency));
+                    if handle.dependencies.is_empty() {
+                        if let Some(b) = handle.pending.take() {
+                            self.finish(id, Ok(Payload::Raw(b)));
+                        }
+                    }
+                }
+            }
+            Notice::Failed(path, e) => {
+                if let Ok(id) = self.id_of(&path) {
+                    let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+                    if handle.pending.take().is_some() {
+                        handle.dependencies.clear();
+                        self.finish(id, Err(Error::DependencyFailed(path, Box::new(e))));
+                    }
+                }
+            }
+            Notice::Reloaded(path) => {
+                if let Ok(id) = self.id_of(&path) {
+                    let handle = self.asset_handles.get_mut(id).expect("id was just resolved");
+                    // Assets inserted with `insert_raw` have no TransferSupplement to reload them with
+                    let supp = match &handle.supp {
+                        Some(supp) if handle.status.eq(&LoadStatus::Loaded) && !handle.reloading => supp.clone(),
+                        _ => return,
+                    };
+                    self.tokens += 1;
+                    let construct = match self.on_loader {
+                        Some((make, _)) => Some(make(&self.data, &handle.data, &path)),
+                        None => None,
+                    };
+                    let request = Request::Load {
+                        manager: self.loader_id,
+                        path,
+                        supp,
+                        priority: 0,
+                        token: self.tokens,
+                        construct,
+                    };
+                    if self.load_send.unbounded_send(request).is_ok() {
+                        handle.token = self.tokens;
+                        handle.reloading = true;
+                    }
                 }
             }
         }
     }
-    pub fn strong_count<P: AsRef<Path>>(&mut self, path: P) -> Option<usize> {
-        Some(Arc::strong_count(
-            self.asset_handles.get(path.as_ref())?.get()?,
-        ))
+    pub fn strong_count<K: AssetKey<A>>(&mut self, key: K) -> Option<usize> {
+        Some(Arc::strong_count(self.handle(key).ok()?.arc()?))
+    }
+    /// Resolve a key to a valid `AssetId`.
+    fn id_of<K: AssetKey<A>>(&self, key: K) -> Result<AssetId<A>, Error> {
+        let id = key.resolve(&self.ids)?;
+        match self.asset_handles.get(id) {
+            Some(_) => Ok(id),
+            None => Err(Error::StaleId),
+        }
+    }
+    fn handle<K: AssetKey<A>>(&self, key: K) -> Result<&AssetHandle<A, L>, Error> {
+        let id = key.resolve(&self.ids)?;
+        self.asset_handles.get(id).ok_or(Error::StaleId)
+    }
+    fn handle_mut<K: AssetKey<A>>(&mut self, key: K) -> Result<&mut AssetHandle<A, L>, Error> {
+        let id = key.resolve(&self.ids)?;
+        self.asset_handles.get_mut(id).ok_or(Error::StaleId)
     }
 }
 
+/// How long `auto_unload` keeps unreferenced Assets loaded.
+#[derive(Copy, Clone)]
+enum Grace {
+    Time(Duration),
+    Maintains(u64),
+}
+
+/// Returns the modification time the Source of the Loader reports for a path.
+fn modified<S: Source>(source: &SharedSource<S>, path: &Path) -> Option<SystemTime> {
+    source.get()?.modified(path)
+}
+
 impl<A, L> Iterator for Manager<A, L>
 where
     A: Asset<L>,
     L: Loader,
 {
-    type Item = Option<Arc<A::Structure>>;
+    type Item = Option<AssetRef<A::Structure>>;
     fn next(&mut self) -> Option<Self::Item> {
         self.asset_handles
-            .iter()
+            .values()
             .next()
-            .map(|(_, a)| a.get().map(|a| a.clone()))
+            .map(|a| a.get())
     }
 }
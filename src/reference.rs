@@ -0,0 +1,117 @@
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::Sender,
+        Arc, Weak,
+    },
+};
+
+/// Counts the `AssetRef`s of one loaded Asset and tells its Manager when the last one is dropped.
+pub(crate) struct Tracker {
+    count: AtomicUsize,
+    key: (u32, u32),
+    release: Option<Sender<(u32, u32)>>,
+}
+
+impl Tracker {
+    pub(crate) fn new(key: (u32, u32), release: Sender<(u32, u32)>) -> Arc<Self> {
+        Arc::new(Self {
+            count: AtomicUsize::new(0),
+            key,
+            release: Some(release),
+        })
+    }
+    /// A Tracker that does not notify any Manager, used for fallback Assets.
+    pub(crate) fn detached() -> Arc<Self> {
+        Arc::new(Self {
+            count: AtomicUsize::new(0),
+            key: (0, 0),
+            release: None,
+        })
+    }
+    /// Returns the number of `AssetRef`s currently alive.
+    pub(crate) fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+/// `AssetRef` is a strong reference to a loaded Asset. `T` is the Assets `Structure`.
+///
+/// While an `AssetRef` is alive, `auto_unload` will not unload the Asset.
+/// When the last `AssetRef` of an Asset is dropped, its Manager is notified and
+/// may unload the Asset during its next `maintain`.
+pub struct AssetRef<T> {
+    asset: Arc<T>,
+    tracker: Arc<Tracker>,
+}
+
+/// `WeakAssetRef` references a loaded Asset without keeping it loaded.
+pub struct WeakAssetRef<T> {
+    asset: Weak<T>,
+    tracker: Weak<Tracker>,
+}
+
+impl<T> AssetRef<T> {
+    pub(crate) fn new(asset: Arc<T>, tracker: Arc<Tracker>) -> Self {
+        tracker.count.fetch_add(1, Ordering::SeqCst);
+        Self { asset, tracker }
+    }
+    /// Create a `WeakAssetRef` to the same Asset.
+    pub fn downgrade(this: &Self) -> WeakAssetRef<T> {
+        WeakAssetRef {
+            asset: Arc::downgrade(&this.asset),
+            tracker: Arc::downgrade(&this.tracker),
+        }
+    }
+    /// Returns the `Arc` of the Asset. Clones of it do not keep the Asset loaded.
+    pub fn arc(this: &Self) -> &Arc<T> {
+        &this.asset
+    }
+}
+
+impl<T> WeakAssetRef<T> {
+    /// Returns an `AssetRef` if the Asset is still alive.
+    pub fn upgrade(&self) -> Option<AssetRef<T>> {
+        Some(AssetRef::new(self.asset.upgrade()?, self.tracker.upgrade()?))
+    }
+}
+
+impl<T> Clone for AssetRef<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.asset.clone(), self.tracker.clone())
+    }
+}
+
+impl<T> Clone for WeakAssetRef<T> {
+    fn clone(&self) -> Self {
+        Self {
+            asset: self.asset.clone(),
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+impl<T> Drop for AssetRef<T> {
+    fn drop(&mut self) {
+        if self.tracker.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(release) = &self.tracker.release {
+                let _ = release.send(self.tracker.key);
+            }
+        }
+    }
+}
+
+impl<T> Deref for AssetRef<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.asset
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AssetRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.asset, f)
+    }
+}
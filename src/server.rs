@@ -0,0 +1,128 @@
+use crate::{
+    loaders::{LoadStatus, Loader},
+    Asset, AssetId, AssetKey, AssetRef, Error, Manager,
+};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    path::Path,
+};
+
+/// The part of a `Manager` the `AssetServer` can use without knowing its Asset type.
+trait AnyManager {
+    fn maintain(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<A, L> AnyManager for Manager<A, L>
+where
+    A: Asset<L> + 'static,
+    L: Loader + 'static,
+{
+    fn maintain(&mut self) {
+        Manager::maintain(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// AssetServer owns one `Manager` per Asset type and gives access to all of them by the Asset type.
+///
+/// Build the Managers with a `Builder` as usual and add them to the server,
+/// they keep sharing the Loader created by the Builder.
+/// Accessing an Asset type whose Manager has not been added returns `Error::NoManager` or None.
+pub struct AssetServer<L>
+where
+    L: Loader,
+{
+    managers: HashMap<TypeId, Box<dyn AnyManager>>,
+    order: Vec<TypeId>,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: Loader + 'static> AssetServer<L> {
+    /// Construct a new, empty `AssetServer`.
+    pub fn new() -> Self {
+        Self {
+            managers: HashMap::new(),
+            order: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+    /// Add the Manager of the Asset type `A`. Returns the previous Manager of `A` if there was one.
+    pub fn add<A: Asset<L> + 'static>(&mut self, manager: Manager<A, L>) -> Option<Manager<A, L>> {
+        let key = TypeId::of::<A>();
+        let previous = self.managers.insert(key, Box::new(manager));
+        if previous.is_none() {
+            self.order.push(key);
+        }
+        previous.map(|m| {
+            *m.into_any()
+                .downcast::<Manager<A, L>>()
+                .unwrap_or_else(|_| unreachable!("Managers are stored under the TypeId of their Asset"))
+        })
+    }
+    /// Returns the Manager of the Asset type `A`, if it has been added.
+    pub fn manager<A: Asset<L> + 'static>(&self) -> Option<&Manager<A, L>> {
+        self.managers.get(&TypeId::of::<A>())?.as_any().downcast_ref()
+    }
+    /// Returns the Manager of the Asset type `A` mutably, if it has been added.
+    pub fn manager_mut<A: Asset<L> + 'static>(&mut self) -> Option<&mut Manager<A, L>> {
+        self.managers.get_mut(&TypeId::of::<A>())?.as_any_mut().downcast_mut()
+    }
+    /// Insert an Assets Path into the Manager of `A`. See `Manager::insert`.
+    pub fn insert<A: Asset<L> + 'static>(
+        &mut self,
+        path: impl AsRef<Path>,
+        data: A::AssetSupplement,
+    ) -> Result<AssetId<A>, Error> {
+        Ok(self.try_manager_mut::<A>()?.insert(path, data))
+    }
+    /// Load an Asset known to the Manager of `A` with the default TransferSupplement. See `Manager::load`.
+    pub fn load<A: Asset<L> + 'static>(&mut self, key: impl AssetKey<A>) -> Result<(), Error>
+    where
+        L::TransferSupplement: Default,
+    {
+        self.try_manager_mut::<A>()?.load(key, L::TransferSupplement::default())
+    }
+    /// Load an Asset known to the Manager of `A` with the given TransferSupplement. See `Manager::load`.
+    pub fn load_with<A: Asset<L> + 'static>(&mut self, key: impl AssetKey<A>, supp: L::TransferSupplement) -> Result<(), Error> {
+        self.try_manager_mut::<A>()?.load(key, supp)
+    }
+    /// Returns an Asset known to the Manager of `A`. See `Manager::get`.
+    pub fn get<A: Asset<L> + 'static>(&self, key: impl AssetKey<A>) -> Option<AssetRef<A::Structure>> {
+        self.manager::<A>()?.get(key)
+    }
+    /// Returns the LoadStatus of an Asset known to the Manager of `A`. See `Manager::status`.
+    pub fn status<A: Asset<L> + 'static>(&self, key: impl AssetKey<A>) -> Option<LoadStatus> {
+        self.manager::<A>()?.status(key)
+    }
+    /// Maintain every Manager in the order they were added.
+    pub fn maintain_all(&mut self) {
+        for key in &self.order {
+            if let Some(manager) = self.managers.get_mut(key) {
+                manager.maintain();
+            }
+        }
+    }
+    fn try_manager_mut<A: Asset<L> + 'static>(&mut self) -> Result<&mut Manager<A, L>, Error> {
+        self.manager_mut::<A>()
+            .ok_or_else(|| Error::NoManager(std::any::type_name::<A>()))
+    }
+}
+
+impl<L: Loader + 'static> Default for AssetServer<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
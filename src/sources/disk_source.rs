@@ -1,15 +1,46 @@
 use super::Source;
-use std::{io::Read, path::PathBuf};
+use crate::BoxError;
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
-pub type DiskSource = ();
+/// DiskSource reads whole files. Relative paths are read from the root directory, if one is set.
+#[derive(Clone, Default, Debug)]
+pub struct DiskSource {
+    root: Option<PathBuf>,
+}
+
+impl DiskSource {
+    /// Construct a DiskSource reading relative paths from `root`.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+        }
+    }
+    fn resolve(&self, path: &Path) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(path),
+            None => path.into(),
+        }
+    }
+}
 
 impl Source for DiskSource {
     type Input = PathBuf;
+    type Supplement = ();
     type Output = Vec<u8>;
-    fn load(path: Self::Input) -> Result<Self::Output, Box<dyn std::error::Error>> {
-        let mut file = std::fs::File::open(&path)?;
+    fn load(&self, path: Self::Input, _: &()) -> Result<Self::Output, BoxError> {
+        let mut file = std::fs::File::open(self.resolve(&path))?;
         let mut contents = vec![];
         file.read_to_end(&mut contents)?;
         Ok(contents)
     }
+    fn size(output: &Self::Output) -> usize {
+        output.len()
+    }
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(self.resolve(path)).and_then(|m| m.modified()).ok()
+    }
 }
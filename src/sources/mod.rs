@@ -1,9 +1,31 @@
 mod disk_source;
 pub use disk_source::DiskSource;
-use std::error::Error;
+use crate::BoxError;
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock},
+    time::SystemTime,
+};
 
+/// A Source is owned by a `Loader` and performs the loads it receives.
+///
+/// The Supplement is the TransferSupplement passed to `Manager::load`, e.g. a byte range or a read hint.
 pub trait Source {
     type Input;
+    type Supplement;
     type Output;
-    fn load(item: Self::Input) -> Result<Self::Output, Box<dyn Error>>;
+    fn load(&self, item: Self::Input, supp: &Self::Supplement) -> Result<Self::Output, BoxError>;
+    /// Returns the size of the loaded data in bytes, used by `MemoryLoaderConfig::bytes_per_second`.
+    /// By default the output has a size of 0.
+    fn size(_output: &Self::Output) -> usize {
+        0
+    }
+    /// Returns when the data at the path was last modified, used by `Manager::watch`.
+    /// By default no modification times are known, so Assets are never reloaded.
+    fn modified(&self, _path: &Path) -> Option<SystemTime> {
+        None
+    }
 }
+
+/// The Source of a Loader, shared with the Managers of its `Builder` once the Loader is created.
+pub(crate) type SharedSource<S> = Arc<OnceLock<Arc<S>>>;
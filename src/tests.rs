@@ -1,7 +1,13 @@
 use super::*;
-use loaders::{LoadStatus, MemoryLoader};
+use loaders::{ImmediateLoader, LoadStatus, MemoryLoader, MemoryLoaderConfig, ThreadPoolLoader};
 use serde::Deserialize;
-use std::{io::ErrorKind, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 /// TestStruct demonstrates implementing Asset
 #[derive(Deserialize)]
@@ -9,7 +15,7 @@ struct TestStruct {
     _s: String,
 }
 
-impl Asset<MemoryLoader> for TestStruct {
+impl<L: Loader<Source = DiskSource>> Asset<L> for TestStruct {
     type Structure = TestStruct;
     type AssetSupplement = ();
     type ManagerSupplement = ();
@@ -17,9 +23,11 @@ impl Asset<MemoryLoader> for TestStruct {
         b: Vec<u8>,
         _: &Self::AssetSupplement,
         _: &Self::ManagerSupplement,
-    ) -> Result<Self, std::io::Error> {
-        ron::de::from_bytes::<TestStruct>(&b)
-            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+    ) -> Result<Self, BoxError> {
+        Ok(ron::de::from_bytes::<TestStruct>(&b)?)
+    }
+    fn size(a: &Self) -> usize {
+        a._s.len()
     }
 }
 
@@ -32,7 +40,7 @@ fn it_works() {
     let _path_to_testfilecopy = std::env::current_dir()
         .unwrap()
         .join("assets/TestAssetCopy.ron");
-    let mut builder = builder::Builder::new();
+    let mut builder = builder::Builder::<MemoryLoader>::new();
 
     //default manager
     let mut manager1 = builder.create_manager::<TestStruct>(());
@@ -46,7 +54,7 @@ fn it_works() {
         .auto_unload()
         .auto_dropout();
 
-    let loader = builder.finish_loader(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
     async_std::task::spawn(loader.run());
     {
         //default demonstration
@@ -84,7 +92,7 @@ fn it_works() {
         //auto-dropout demonstration
         manager3.insert(&path1, ());
         manager3.maintain(); //Assethandle will be dropped during this maintain. Cant be loaded afterwards.
-        assert!(manager3.load(&path1,()).is_err()); //Cant be loaded
+        assert!(matches!(manager3.load(&path1,()), Err(Error::NotRegistered(_)))); //Cant be loaded
         assert!(manager3.status(&path1).eq(&None)); //Asset not loaded
         manager3.insert(&path1, ());
         manager3.load(&path1,()).unwrap();
@@ -121,9 +129,9 @@ fn test_load_get() {
     let path2 = std::env::current_dir()
         .unwrap()
         .join("assets/TestAssetCopy.ron");
-    let mut builder = builder::Builder::new();
+    let mut builder = builder::Builder::<MemoryLoader>::new();
     let mut manager = builder.create_manager::<TestStruct>(());
-    let loader = builder.finish_loader(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
     async_std::task::spawn(loader.run());
 
     manager.insert(&path, ());
@@ -143,3 +151,1056 @@ fn test_load_get() {
     assert!(s1._s.eq(&String::from("12341234")));
     assert!(s2._s.eq(&String::from("123412345")));
 }
+
+#[test]
+fn test_failed() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAssetBroken.ron");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+    async_std::task::spawn(loader.run());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap();
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while manager.status(&path).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    assert!(manager.status(&path).eq(&Some(LoadStatus::Failed))); //Asset could not be constructed
+    assert!(manager.get(&path).is_none());
+    assert!(matches!(manager.error(&path), Some(Error::DecodeFailed(..)))); //The reason is kept until the next load
+    manager.load(&path, ()).unwrap(); //Failed Assets can be retried
+    assert!(manager.status(&path).eq(&Some(LoadStatus::Loading)));
+}
+
+#[test]
+fn test_failed_once() {
+    let path = std::env::current_dir().unwrap().join("assets"); //a directory cant be read by DiskSource
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+    async_std::task::spawn(loader.run());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap();
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while manager.status(&path).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    assert!(manager.status(&path).eq(&Some(LoadStatus::Failed)));
+    assert!(matches!(manager.error(&path), Some(Error::SourceFailed(..))));
+    assert_eq!(manager.get_failed_once(), vec![path.clone()]); //Failures are reported once
+    assert!(manager.get_failed_once().is_empty());
+
+    let missing = path.join("Missing.ron");
+    manager.insert(&missing, ());
+    manager.load(&missing, ()).unwrap();
+    assert!(manager.get_blocking(&missing).is_none());
+    assert!(matches!(manager.error(&missing), Some(Error::FileNotFound(_))));
+}
+
+#[test]
+fn test_loader_exits() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+    let running = async_std::task::spawn(loader.run());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap();
+    assert!(manager.get_blocking(&path).is_some());
+    drop(manager); //the last Manager is gone, so the loader stops
+    async_std::task::block_on(async_std::future::timeout(Duration::from_secs(1), running))
+        .expect("loader did not exit");
+}
+
+#[test]
+fn test_loader_shutdown() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, mut handle) = builder.finish_loader(MemoryLoaderConfig::default());
+    async_std::task::spawn(loader.run());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap();
+    assert!(manager.get_blocking(&path).is_some());
+    assert!(!handle.is_finished());
+    handle.shutdown(); //stop the loader while the Manager is still alive
+    async_std::task::block_on(async_std::future::timeout(Duration::from_secs(1), handle.join()))
+        .expect("loader did not shut down");
+    manager.unload(&path);
+    assert!(matches!(manager.load(&path, ()), Err(Error::LoaderDisconnected(_))));
+    assert!(manager.status(&path).eq(&Some(LoadStatus::NotLoaded)));
+}
+
+#[test]
+fn test_thread_pool_loader() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let path2 = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAssetCopy.ron");
+    let mut builder = builder::Builder::<ThreadPoolLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(2); //no async runtime needed
+
+    manager.insert(&path, ());
+    manager.insert(&path2, ());
+    manager.load(&path, ()).unwrap();
+    manager.load(&path2, ()).unwrap();
+    let mut loaded = Vec::new();
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while loaded.len() < 2 {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5)); //wait for the workers
+        manager.maintain();
+        loaded.append(&mut manager.get_loaded_once());
+    }
+    assert!(manager.get(&path).unwrap()._s.eq(&String::from("12341234")));
+    assert!(manager.get(&path2).unwrap()._s.eq(&String::from("123412345")));
+    drop(manager); //the workers stop once the last Manager is gone
+    loader.join();
+}
+
+#[test]
+fn test_immediate_loader() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(()).auto_unload();
+    let (_loader, mut handle) = builder.finish_loader(());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap(); //the file is read right here
+    manager.maintain(); //no need to wait for anything
+    assert!(manager.status(&path).eq(&Some(LoadStatus::Loaded)));
+    manager.maintain(); //unreferenced, so auto_unload removes it
+    assert!(manager.status(&path).eq(&Some(LoadStatus::NotLoaded)));
+    manager.load(&path, ()).unwrap();
+    manager.maintain();
+    assert!(manager.get(&path).unwrap()._s.eq(&String::from("12341234")));
+    handle.shutdown();
+    assert!(handle.is_finished());
+    assert!(matches!(manager.load(&path, ()), Err(Error::LoaderDisconnected(_))));
+}
+
+#[test]
+fn test_load_async() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let broken = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAssetBroken.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(());
+
+    manager.insert(&path, ());
+    manager.insert(&broken, ());
+    let asset = manager.load_async(&path, ());
+    let asset_again = manager.load_async(&path, ()); //waits for the same load
+    let failed = manager.load_async(&broken, ());
+    let failed_again = manager.load_async(&broken, ());
+    manager.maintain(); //the futures complete during maintain
+    let asset = futures::executor::block_on(asset).unwrap();
+    assert!(asset._s.eq(&String::from("12341234")));
+    assert!(futures::executor::block_on(asset_again).is_ok());
+    assert!(matches!(futures::executor::block_on(failed), Err(Error::DecodeFailed(..))));
+    assert!(matches!(futures::executor::block_on(failed_again), Err(Error::DecodeFailed(..))));
+    let loaded = manager.load_async(&path, ()); //already loaded, so it is ready immediately
+    assert!(futures::executor::block_on(loaded).is_ok());
+
+    let unloaded = manager.load_async(&broken, ());
+    manager.unload(&broken);
+    assert!(matches!(futures::executor::block_on(unloaded), Err(Error::Cancelled(_))));
+    let unknown = manager.load_async("unknown.ron", ());
+    assert!(matches!(futures::executor::block_on(unknown), Err(Error::NotRegistered(_))));
+}
+
+#[test]
+fn test_get_blocking_concurrent() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let path2 = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAssetCopy.ron");
+    let broken = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAssetBroken.ron");
+    let mut builder = builder::Builder::<ThreadPoolLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(3);
+
+    manager.insert(&path, ());
+    manager.insert(&path2, ());
+    manager.insert(&broken, ());
+    manager.load(&broken, ()).unwrap();
+    manager.load(&path, ()).unwrap();
+    manager.load(&path2, ()).unwrap();
+    //whichever result arrives first, each one ends up in its own handle
+    assert!(manager.get_blocking(&path2).unwrap()._s.eq(&String::from("123412345")));
+    assert!(manager.get_blocking(&path).unwrap()._s.eq(&String::from("12341234")));
+    assert!(manager.get_blocking(&broken).is_none());
+    assert!(manager.status(&broken).eq(&Some(LoadStatus::Failed)));
+    let mut loaded = manager.get_loaded_once();
+    loaded.sort();
+    assert_eq!(loaded, vec![path.clone(), path2.clone()]);
+    assert_eq!(manager.get_failed_once(), vec![broken]);
+}
+
+#[test]
+fn test_get_blocking_timeout() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+
+    manager.insert(&path, ());
+    manager.load(&path, ()).unwrap();
+    //the loader is not running yet, so nothing arrives
+    assert!(manager.get_blocking_timeout(&path, Duration::from_millis(20)).is_none());
+    assert!(manager.status(&path).eq(&Some(LoadStatus::Loading)));
+    async_std::task::spawn(loader.run());
+    assert!(manager.get_blocking_timeout(&path, Duration::from_secs(1)).is_some());
+}
+
+#[test]
+fn test_asset_ids() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, ());
+    assert_eq!(manager.insert(&path, ()), id); //known paths return their id
+    assert_eq!(manager.id(&path), Some(id));
+    assert_eq!(manager.path(id), Some(path.as_path()));
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    assert!(manager.get(id).unwrap()._s.eq(&String::from("12341234")));
+
+    manager.drop(id);
+    let new_id = manager.insert(&path, ()); //the slot is reused with a new generation
+    assert_ne!(new_id, id);
+    assert!(manager.status(id).is_none()); //the stale id does not alias the new entry
+    assert!(matches!(manager.load(id, ()), Err(Error::StaleId)));
+    assert!(manager.status(new_id).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(manager.status(&path).eq(&Some(LoadStatus::NotLoaded)));
+}
+
+#[test]
+fn test_asset_refs() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(()).auto_unload();
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, ());
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    let strong = manager.get(id).unwrap();
+    let strong2 = strong.clone();
+    let weak = manager.get_weak(id).unwrap();
+    let arc = AssetRef::arc(&strong).clone(); //a plain Arc does not keep the Asset loaded
+    drop(strong);
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded))); //strong2 is still alive
+    assert!(weak.upgrade().is_some());
+    drop(strong2);
+    manager.maintain(); //the last AssetRef is gone
+    assert!(manager.status(id).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(arc._s.eq(&String::from("12341234")));
+    drop(arc);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn test_hot_reload() {
+    let path = std::env::temp_dir().join("assetmanage_hot_reload.ron");
+    std::fs::write(&path, "TestStruct(_s:\"before\")").unwrap();
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder
+        .create_manager::<TestStruct>(())
+        .watch(Duration::from_millis(0));
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, ());
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    let before = manager.get(id).unwrap();
+    assert!(before._s.eq(&String::from("before")));
+    manager.maintain(); //nothing changed
+    assert!(manager.get_reloaded_once().is_empty());
+
+    std::fs::write(&path, "TestStruct(_s:\"after\")").unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() + Duration::from_secs(10)).unwrap();
+    manager.maintain(); //the change is detected, loaded and swapped in
+    assert_eq!(manager.get_reloaded_once(), vec![path.clone()]);
+    assert!(manager.get(id).unwrap()._s.eq(&String::from("after")));
+    assert!(before._s.eq(&String::from("before"))); //old references keep the old Asset
+
+    std::fs::write(&path, "broken").unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() + Duration::from_secs(20)).unwrap();
+    manager.maintain(); //a failed reload keeps the previous Asset
+    assert_eq!(manager.get_failed_once(), vec![path.clone()]);
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    assert!(manager.get(id).unwrap()._s.eq(&String::from("after")));
+
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder
+        .create_manager::<TestStruct>(())
+        .watch(Duration::from_millis(0));
+    let _loader = builder.finish_loader_with_source(DiskSource::with_root(std::env::temp_dir()), ());
+    std::fs::write(&path, "TestStruct(_s:\"before\")").unwrap();
+    let id = manager.insert(path.file_name().unwrap(), ()); //relative to the root of the Source
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    std::fs::write(&path, "TestStruct(_s:\"rooted\")").unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() + Duration::from_secs(30)).unwrap();
+    manager.maintain();
+    assert!(manager.get(id).unwrap()._s.eq(&String::from("rooted")));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_versions() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, ());
+    assert_eq!(manager.version(id), Some(0)); //nothing stored yet
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    let (asset, version) = manager.get_with_version(id).unwrap();
+    assert_eq!(version, 1);
+    assert!(asset._s.eq(&String::from("12341234")));
+    manager.insert_raw(&path, TestStruct { _s: String::from("raw") }, ()); //replacing bumps the version
+    let (asset, version) = manager.get_with_version(id).unwrap();
+    assert_eq!(version, 2);
+    assert!(asset._s.eq(&String::from("raw")));
+    manager.unload(id);
+    assert_eq!(manager.version(id), Some(2)); //versions never go back
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    assert_eq!(manager.version(id), Some(3));
+}
+
+/// TestBundle demonstrates declaring dependencies on the TestStructs of another Manager.
+#[derive(Deserialize)]
+struct TestBundle {
+    parts: Vec<String>,
+}
+
+impl<L: Loader<Source = DiskSource, TransferSupplement = ()>> Asset<L> for TestBundle {
+    type Structure = TestBundle;
+    type AssetSupplement = ();
+    type ManagerSupplement = ManagerId;
+    fn construct(
+        b: Vec<u8>,
+        _: &Self::AssetSupplement,
+        _: &Self::ManagerSupplement,
+    ) -> Result<Self, BoxError> {
+        Ok(ron::de::from_bytes::<TestBundle>(&b)?)
+    }
+    fn dependencies(b: &Vec<u8>, _: &Self::AssetSupplement, parts: &Self::ManagerSupplement) -> Vec<(Dependency, ())> {
+        let dir = std::env::current_dir().unwrap().join("assets");
+        ron::de::from_bytes::<TestBundle>(b)
+            .map(|bundle| bundle.parts.iter().map(|p| (Dependency::new(*parts, dir.join(p)), ())).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_dependencies() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut parts = builder
+        .create_manager::<TestStruct>(())
+        .insert_dependencies(|_| ());
+    let mut bundles = builder.create_manager::<TestBundle>(parts.manager_id());
+    let _loader = builder.finish_loader(());
+
+    let bundle = bundles.insert(dir.join("TestBundle.ron"), ());
+    bundles.load(bundle, ()).unwrap();
+    assert!(bundles.get_blocking(bundle).is_none()); //the parts are only loaded by the maintain of their Manager
+    bundles.maintain(); //the bundle waits for its parts
+    assert!(bundles.status(bundle).eq(&Some(LoadStatus::Loading)));
+    assert!(parts.status(dir.join("TestAsset.ron")).is_none());
+    parts.maintain(); //the parts are inserted and loaded
+    assert!(parts.status(dir.join("TestAsset.ron")).eq(&Some(LoadStatus::Loaded)));
+    assert!(parts.status(dir.join("TestAssetCopy.ron")).eq(&Some(LoadStatus::Loaded)));
+    bundles.maintain();
+    assert!(bundles.status(bundle).eq(&Some(LoadStatus::Loaded)));
+    assert_eq!(bundles.get(bundle).unwrap().parts.len(), 2);
+
+    let broken = bundles.insert(dir.join("TestBundleBroken.ron"), ());
+    bundles.load(broken, ()).unwrap();
+    bundles.maintain();
+    parts.maintain(); //TestAsset.ron is already loaded, TestAssetBroken.ron fails
+    bundles.maintain();
+    assert!(bundles.status(broken).eq(&Some(LoadStatus::Failed)));
+    assert!(matches!(bundles.error(broken), Some(Error::DependencyFailed(_, e)) if matches!(**e, Error::DecodeFailed(..))));
+
+    parts.insert_raw(dir.join("TestAssetCopy.ron"), TestStruct { _s: String::from("raw") }, ());
+    bundles.maintain(); //replacing a part reloads the bundle depending on it
+    parts.maintain();
+    bundles.maintain();
+    assert_eq!(bundles.get_reloaded_once(), vec![dir.join("TestBundle.ron")]);
+    assert_eq!(bundles.version(bundle), Some(2));
+    assert_eq!(parts.version(dir.join("TestAssetCopy.ron")), Some(2)); //the replaced part is not loaded again
+
+    bundles.unload(bundle); //unloaded bundles no longer depend on their parts
+    parts.insert_raw(dir.join("TestAssetCopy.ron"), TestStruct { _s: String::from("raw") }, ());
+    parts.maintain();
+    bundles.maintain();
+    assert!(bundles.status(bundle).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(bundles.get_reloaded_once().is_empty());
+}
+
+#[test]
+fn test_dependency_cycles() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut bundles = builder
+        .create_manager::<TestBundle>(ManagerId(0)) //the bundles depend on bundles of the same Manager
+        .insert_dependencies(|_| ());
+    let _loader = builder.finish_loader(());
+
+    let own = bundles.insert(dir.join("TestCycleSelf.ron"), ());
+    bundles.load(own, ()).unwrap();
+    bundles.maintain();
+    assert!(bundles.status(own).eq(&Some(LoadStatus::Failed)));
+    assert!(matches!(bundles.error(own), Some(Error::DependencyFailed(_, e)) if matches!(**e, Error::DependencyCycle(..))));
+
+    let a = bundles.insert(dir.join("TestCycleA.ron"), ());
+    bundles.load(a, ()).unwrap();
+    bundles.maintain();
+    assert!(bundles.status(a).eq(&Some(LoadStatus::Failed)));
+    assert!(bundles.status(dir.join("TestCycleB.ron")).eq(&Some(LoadStatus::Failed)));
+    assert!(bundles.get_blocking(a).is_none());
+}
+
+#[test]
+fn test_asset_server() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let parts = builder
+        .create_manager::<TestStruct>(())
+        .insert_dependencies(|_| ());
+    let bundles = builder.create_manager::<TestBundle>(parts.manager_id());
+    let _loader = builder.finish_loader(());
+    let mut server = AssetServer::new();
+    assert!(server.add(parts).is_none());
+    assert!(server.add(bundles).is_none());
+
+    let bundle = server.insert::<TestBundle>(dir.join("TestBundle.ron"), ()).unwrap();
+    server.load::<TestBundle>(bundle).unwrap();
+    server.insert::<TestStruct>(dir.join("TestAssetBroken.ron"), ()).unwrap();
+    server.load::<TestStruct>(dir.join("TestAssetBroken.ron")).unwrap();
+    server.maintain_all(); //the bundle requests its parts
+    server.maintain_all(); //the parts are loaded and the bundle is constructed
+    assert_eq!(server.get::<TestBundle>(bundle).unwrap().parts.len(), 2);
+    assert!(server.get::<TestStruct>(dir.join("TestAsset.ron")).unwrap()._s.eq(&String::from("12341234")));
+    assert!(server.status::<TestStruct>(dir.join("TestAssetBroken.ron")).eq(&Some(LoadStatus::Failed)));
+    assert_eq!(server.manager_mut::<TestStruct>().unwrap().get_failed_once().len(), 1);
+
+    assert!(matches!(server.insert::<TestThread>(dir.join("TestAsset.ron"), ()), Err(Error::NoManager(_))));
+    assert!(matches!(server.load::<TestThread>(dir.join("TestAsset.ron")), Err(Error::NoManager(_))));
+    assert!(server.get::<TestThread>(dir.join("TestAsset.ron")).is_none());
+    assert!(server.status::<TestThread>(dir.join("TestAsset.ron")).is_none());
+}
+
+#[test]
+fn test_cache() {
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(()).cache(16);
+    let _loader = builder.finish_loader(());
+    let raw = |s: &str| TestStruct { _s: String::from(s) };
+
+    let a = manager.insert_raw("a", raw("aaaaaaaa"), ());
+    let b = manager.insert_raw("b", raw("bbbbbbbb"), ());
+    manager.maintain(); //16 bytes fit into the budget
+    assert!(manager.status(a).eq(&Some(LoadStatus::Loaded)));
+    let c = manager.insert_raw("c", raw("cccccccc"), ());
+    manager.maintain(); //a is the least recently used
+    assert!(manager.status(a).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(manager.status(b).eq(&Some(LoadStatus::Loaded)));
+
+    let held = manager.get(c).unwrap();
+    drop(manager.get(b).unwrap()); //b becomes the most recently used
+    manager.maintain();
+    let d = manager.insert_raw("d", raw("dddddddddddddddd"), ());
+    manager.maintain(); //c is referenced, so only b and d can be evicted
+    assert!(manager.status(b).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(manager.status(c).eq(&Some(LoadStatus::Loaded)));
+    assert!(manager.status(d).eq(&Some(LoadStatus::Loaded)));
+    drop(held);
+    manager.maintain(); //c is released after d was loaded, so d is evicted
+    assert!(manager.status(c).eq(&Some(LoadStatus::Loaded)));
+    assert!(manager.status(d).eq(&Some(LoadStatus::NotLoaded)));
+}
+
+#[test]
+fn test_unload_after() {
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(()).unload_after_maintains(2);
+    let mut timed = builder
+        .create_manager::<TestStruct>(())
+        .unload_after(Duration::from_millis(50));
+    let _loader = builder.finish_loader(());
+    let raw = || TestStruct { _s: String::from("raw") };
+
+    let id = manager.insert_raw("a", raw(), ());
+    manager.maintain(); //the unreferenced Asset is noticed
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    let again = manager.get(id).unwrap(); //referenced again during the grace period
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    drop(again);
+    manager.maintain(); //the new release restarts the grace period
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    manager.maintain();
+    assert!(manager.status(id).eq(&Some(LoadStatus::NotLoaded)));
+
+    let id = timed.insert_raw("a", raw(), ());
+    timed.maintain();
+    assert!(timed.status(id).eq(&Some(LoadStatus::Loaded)));
+    std::thread::sleep(Duration::from_millis(60));
+    timed.maintain();
+    assert!(timed.status(id).eq(&Some(LoadStatus::NotLoaded)));
+}
+
+#[test]
+fn test_pinning() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder
+        .create_manager::<TestStruct>(())
+        .auto_unload()
+        .auto_dropout();
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert_pinned(&path, ());
+    assert!(manager.is_pinned(id));
+    manager.maintain(); //not loaded, but pinned Assets are not dropped
+    assert!(manager.status(id).eq(&Some(LoadStatus::NotLoaded)));
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    manager.maintain(); //unreferenced, but pinned Assets are not unloaded
+    assert!(manager.status(id).eq(&Some(LoadStatus::Loaded)));
+    manager.unpin(id);
+    assert!(!manager.is_pinned(id));
+    manager.maintain(); //unloaded and dropped
+    assert!(manager.status(id).is_none());
+}
+
+#[test]
+fn test_fallback() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder
+        .create_manager::<TestStruct>(())
+        .fallback(TestStruct { _s: String::from("fallback") });
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+    async_std::task::spawn(loader.run());
+
+    let id = manager.insert(dir.join("TestAsset.ron"), ());
+    let (asset, placeholder) = manager.get_or_fallback(id).unwrap(); //not loaded yet
+    assert!(placeholder && asset._s.eq(&String::from("fallback")));
+    manager.load(id, ()).unwrap();
+    let (_, placeholder) = manager.get_or_fallback(id).unwrap(); //still loading
+    assert!(placeholder);
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while manager.status(id).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    let (asset, placeholder) = manager.get_or_fallback(id).unwrap();
+    assert!(!placeholder && asset._s.eq(&String::from("12341234")));
+
+    let broken = manager.insert(dir.join("TestAssetBroken.ron"), ());
+    manager.set_fallback(broken, TestStruct { _s: String::from("broken") });
+    manager.load(broken, ()).unwrap();
+    while manager.status(broken).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    let (asset, placeholder) = manager.get_or_fallback(broken).unwrap(); //failed, the own fallback is preferred
+    assert!(placeholder && asset._s.eq(&String::from("broken")));
+    assert!(manager.get(broken).is_none());
+}
+
+#[test]
+fn test_priorities() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default().max_in_flight(1));
+
+    let a = manager.insert(dir.join("TestAsset.ron"), ());
+    let b = manager.insert(dir.join("TestAssetCopy.ron"), ());
+    let broken = manager.insert(dir.join("TestAssetBroken.ron"), ());
+    manager.load(a, ()).unwrap();
+    manager.load_with_priority(b, (), 1).unwrap();
+    manager.load_with_priority(broken, (), 2).unwrap();
+    manager.set_priority(a, 3); //a was queued first with the lowest priority
+    async_std::task::spawn(loader.run()); //every request is queued before the loader starts
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while manager.status(b).eq(&Some(LoadStatus::Loading)) || manager.status(a).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    assert_eq!(manager.get_loaded_once(), vec![dir.join("TestAsset.ron"), dir.join("TestAssetCopy.ron")]);
+
+    let mut builder = builder::Builder::<MemoryLoader<SlowSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let source = SlowSource::default();
+    let started = source.started.clone();
+    let config = MemoryLoaderConfig::default().max_in_flight(1);
+    let (loader, _) = builder.finish_loader_with_source(source, config);
+    async_std::task::spawn(loader.run());
+    let ids: Vec<_> = ["busy", "low", "raised", "high"].iter().map(|p| manager.insert(p, ())).collect();
+    manager.load(ids[0], ()).unwrap();
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while started.lock().unwrap().is_empty() {
+        assert!(std::time::Instant::now() < deadline, "the load did not start");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    manager.load(ids[1], ()).unwrap(); //queued while the worker is busy
+    manager.load(ids[2], ()).unwrap();
+    manager.load_with_priority(ids[3], (), 100).unwrap();
+    manager.set_priority(ids[2], 50);
+    while ids.iter().any(|id| manager.status(*id).eq(&Some(LoadStatus::Loading))) {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    let order: Vec<std::path::PathBuf> = ["busy", "high", "raised", "low"].iter().map(|p| p.into()).collect();
+    assert_eq!(*started.lock().unwrap(), order);
+}
+
+#[test]
+fn test_cancel() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, ());
+    let cancelled = manager.load_async(id, ()); //loaded right away, the result waits in the channel
+    manager.cancel(id);
+    assert!(manager.status(id).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(matches!(futures::executor::block_on(cancelled), Err(Error::Cancelled(_))));
+    manager.maintain(); //the late result is ignored
+    assert!(manager.status(id).eq(&Some(LoadStatus::NotLoaded)));
+    assert!(manager.get_loaded_once().is_empty());
+
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let (loader, _) = builder.finish_loader(MemoryLoaderConfig::default());
+    let id = manager.insert(&path, ());
+    manager.load(id, ()).unwrap();
+    manager.cancel(id); //removed from the queue before the loader starts
+    manager.load(id, ()).unwrap();
+    async_std::task::spawn(loader.run());
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while manager.status(id).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    std::thread::sleep(Duration::from_millis(20));
+    manager.maintain();
+    assert_eq!(manager.get_loaded_once().len(), 1); //only the second request was loaded
+    assert_eq!(manager.version(id), Some(1));
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let mut builder = builder::Builder::<MemoryLoader<RangeSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let source = RangeSource {
+        root: path.parent().unwrap().into(),
+        loads: loads.clone(),
+    };
+    let (loader, _) = builder.finish_loader_with_source(source, MemoryLoaderConfig::default());
+    let id = manager.insert("TestAsset.ron", ());
+    manager.load(id, None).unwrap();
+    manager.drop(id); //dropping a loading Asset removes its request as well
+    let copy = manager.insert("TestAssetCopy.ron", ());
+    manager.load(copy, None).unwrap();
+    async_std::task::spawn(loader.run());
+    while manager.status(copy).eq(&Some(LoadStatus::Loading)) {
+        assert!(std::time::Instant::now() < deadline, "the load did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    let mut builder = builder::Builder::<MemoryLoader<SlowSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let source = SlowSource::default();
+    let started = source.started.clone();
+    let config = MemoryLoaderConfig::default().max_in_flight(1);
+    let (loader, _) = builder.finish_loader_with_source(source, config);
+    async_std::task::spawn(loader.run());
+    let busy = manager.insert("busy", ());
+    manager.load(busy, ()).unwrap();
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while started.lock().unwrap().is_empty() {
+        assert!(std::time::Instant::now() < deadline, "the load did not start");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let queued = manager.insert("queued", ());
+    manager.load(queued, ()).unwrap();
+    manager.cancel(queued); //cancelled while the worker is busy
+    manager.insert_raw("busy", vec![1], ()); //cancels the running load
+    for _ in 0..10 {
+        std::thread::sleep(Duration::from_millis(10));
+        manager.maintain();
+    }
+    let order: Vec<std::path::PathBuf> = vec!["busy".into()];
+    assert_eq!(*started.lock().unwrap(), order); //the cancelled path never reached the Source
+    assert_eq!(manager.version(busy), Some(1)); //the late result did not replace the inserted Asset
+    assert_eq!(*manager.get(busy).unwrap(), vec![1]);
+    assert!(manager.get_loaded_once().is_empty());
+}
+
+#[test]
+fn test_memory_loader_limits() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<MemoryLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let config = MemoryLoaderConfig::default()
+        .max_in_flight(1)
+        .bytes_per_second(32 * 20); //a 32 byte file every 50ms
+    let (loader, _) = builder.finish_loader(config);
+
+    let paths = ["TestAsset.ron", "TestAssetCopy.ron", "TestAssetBroken.ron"];
+    for path in paths.iter() {
+        let id = manager.insert(dir.join(path), ());
+        manager.load(id, ()).unwrap();
+    }
+    let start = std::time::Instant::now();
+    async_std::task::spawn(loader.run());
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while paths
+        .iter()
+        .any(|path| manager.status(dir.join(path)).eq(&Some(LoadStatus::Loading)))
+    {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(100)); //the second and third load waited
+    assert_eq!(manager.get_loaded_once().len(), 2);
+    assert_eq!(manager.get_failed_once().len(), 1);
+}
+
+/// SlowSource takes a while for every load and records the order the loads started in
+/// and how many loads ran at the same time. It panics when loading the path "panic".
+#[derive(Default)]
+struct SlowSource {
+    running: AtomicUsize,
+    most: Arc<AtomicUsize>,
+    started: Arc<std::sync::Mutex<Vec<std::path::PathBuf>>>,
+}
+
+impl Source for SlowSource {
+    type Input = std::path::PathBuf;
+    type Supplement = ();
+    type Output = Vec<u8>;
+    fn load(&self, path: Self::Input, _: &()) -> Result<Self::Output, BoxError> {
+        if path.to_str() == Some("panic") {
+            panic!("SlowSource cant load {:?}", path);
+        }
+        self.started.lock().unwrap().push(path);
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most.fetch_max(running, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(20));
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(Vec::new())
+    }
+}
+
+#[test]
+fn test_memory_loader_in_flight() {
+    let mut builder = builder::Builder::<MemoryLoader<SlowSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let source = SlowSource::default();
+    let most = source.most.clone();
+    let config = MemoryLoaderConfig::default().max_in_flight(2);
+    let (loader, _) = builder.finish_loader_with_source(source, config);
+
+    let panic = manager.insert("panic", ());
+    manager.load(panic, ()).unwrap();
+    let ids: Vec<_> = (0..6).map(|i| manager.insert(format!("{}", i), ())).collect();
+    for id in ids.iter() {
+        manager.load(*id, ()).unwrap();
+    }
+    async_std::task::spawn(loader.run());
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while ids.iter().chain(Some(&panic)).any(|id| manager.status(*id).eq(&Some(LoadStatus::Loading))) {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        manager.maintain();
+    }
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    assert_eq!(most.load(Ordering::SeqCst), cpus.min(2)); //never more loads than workers
+    assert!(matches!(manager.error(panic), Some(Error::SourceFailed(..)))); //the panic fails only its own load
+    assert_eq!(manager.get_loaded_once().len(), 6);
+
+    let mut builder = builder::Builder::<ThreadPoolLoader<SlowSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let (loader, _) = builder.finish_loader_with_source(SlowSource::default(), 1);
+    let panic = manager.insert("panic", ());
+    let after = manager.insert("after", ());
+    manager.load(panic, ()).unwrap();
+    manager.load(after, ()).unwrap();
+    assert!(manager.get_blocking_timeout(after, Duration::from_secs(5)).is_some()); //the worker survived
+    assert!(matches!(manager.error(panic), Some(Error::SourceFailed(..))));
+    drop(manager);
+    loader.join();
+}
+
+/// TestThread records the thread it was constructed on.
+struct TestThread;
+
+impl<L: Loader<Source = DiskSource>> Asset<L> for TestThread {
+    type Structure = std::thread::ThreadId;
+    type AssetSupplement = ();
+    type ManagerSupplement = ();
+    fn construct(
+        _: Vec<u8>,
+        _: &Self::AssetSupplement,
+        _: &Self::ManagerSupplement,
+    ) -> Result<Self::Structure, BoxError> {
+        Ok(std::thread::current().id())
+    }
+}
+
+#[test]
+fn test_construct_on_loader() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ThreadPoolLoader>::new();
+    let mut threads = builder.create_manager::<TestThread>(()).construct_on_loader();
+    let mut structs = builder.create_manager::<TestStruct>(()).construct_on_loader();
+    let (loader, _) = builder.finish_loader(1);
+
+    threads.insert(dir.join("TestAsset.ron"), ());
+    threads.load(dir.join("TestAsset.ron"), ()).unwrap();
+    for path in ["TestAsset.ron", "TestAssetBroken.ron"].iter() {
+        structs.insert(dir.join(path), ());
+        structs.load(dir.join(path), ()).unwrap();
+    }
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while threads.status(dir.join("TestAsset.ron")).eq(&Some(LoadStatus::Loading))
+        || structs.status(dir.join("TestAssetBroken.ron")).eq(&Some(LoadStatus::Loading))
+    {
+        assert!(std::time::Instant::now() < deadline, "the loads did not finish");
+        std::thread::sleep(Duration::from_millis(5));
+        threads.maintain();
+        structs.maintain();
+    }
+    assert!(*threads.get(dir.join("TestAsset.ron")).unwrap() != std::thread::current().id());
+    assert!(structs.get(dir.join("TestAsset.ron")).unwrap()._s.eq(&String::from("12341234")));
+    assert!(matches!(structs.error(dir.join("TestAssetBroken.ron")), Some(Error::DecodeFailed(..))));
+    drop(threads);
+    drop(structs);
+    loader.join();
+}
+
+#[test]
+fn test_construct_on_loader_dependencies() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut parts = builder
+        .create_manager::<TestStruct>(())
+        .insert_dependencies(|_| ())
+        .construct_on_loader();
+    let mut bundles = builder
+        .create_manager::<TestBundle>(parts.manager_id())
+        .construct_on_loader();
+    let _loader = builder.finish_loader(());
+
+    let bundle = bundles.insert(dir.join("TestBundle.ron"), ());
+    bundles.load(bundle, ()).unwrap();
+    bundles.maintain(); //Assets with dependencies are constructed by the Manager once they are loaded
+    assert!(bundles.status(bundle).eq(&Some(LoadStatus::Loading)));
+    parts.maintain();
+    bundles.maintain();
+    assert_eq!(bundles.get(bundle).unwrap().parts.len(), 2);
+}
+
+#[test]
+fn test_maintain_budget() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader(());
+
+    let paths = ["TestAsset.ron", "TestAssetCopy.ron", "TestAssetBroken.ron"];
+    for path in paths.iter() {
+        let id = manager.insert(dir.join(path), ());
+        manager.load(id, ()).unwrap();
+    }
+    assert_eq!(manager.maintain_max_items(1), 2);
+    assert_eq!(manager.get_loaded_once(), vec![dir.join("TestAsset.ron")]);
+    assert!(manager.status(dir.join("TestAssetCopy.ron")).eq(&Some(LoadStatus::Loading)));
+    assert_eq!(manager.maintain_with_budget(Duration::from_secs(0)), 1); //at least one result per call
+    assert_eq!(manager.maintain_with_budget(Duration::from_secs(1)), 0);
+    assert_eq!(manager.get_loaded_once().len(), 1);
+    assert_eq!(manager.get_failed_once(), vec![dir.join("TestAssetBroken.ron")]);
+
+    let id = manager.id(dir.join("TestAssetCopy.ron")).unwrap();
+    manager.unload(id);
+    manager.load(id, ()).unwrap();
+    assert_eq!(manager.maintain_max_items(0), 1);
+    assert!(manager.get_blocking(id).is_some()); //results left for the next call are not lost
+}
+
+/// RangeSource demonstrates a Source with a root directory that reads the byte range passed to `Manager::load`.
+/// It counts the loads it performed.
+struct RangeSource {
+    root: std::path::PathBuf,
+    loads: Arc<AtomicUsize>,
+}
+
+impl Source for RangeSource {
+    type Input = std::path::PathBuf;
+    type Supplement = Option<std::ops::Range<usize>>;
+    type Output = Vec<u8>;
+    fn load(&self, path: Self::Input, range: &Self::Supplement) -> Result<Self::Output, BoxError> {
+        self.loads.fetch_add(1, Ordering::SeqCst);
+        let b = std::fs::read(self.root.join(path))?;
+        match range {
+            Some(range) => Ok(b.get(range.clone()).ok_or("range out of bounds")?.to_vec()),
+            None => Ok(b),
+        }
+    }
+}
+
+/// TestBytes keeps the loaded data as it is.
+struct TestBytes;
+
+impl<L: Loader> Asset<L> for TestBytes
+where
+    L::Source: Source<Output = Vec<u8>>,
+{
+    type Structure = Vec<u8>;
+    type AssetSupplement = ();
+    type ManagerSupplement = ();
+    fn construct(
+        b: Vec<u8>,
+        _: &Self::AssetSupplement,
+        _: &Self::ManagerSupplement,
+    ) -> Result<Self::Structure, BoxError> {
+        Ok(b)
+    }
+}
+
+/// TestScaled multiplies the length of the loaded data with its AssetSupplement.
+struct TestScaled;
+
+impl<L: Loader<Source = DiskSource>> Asset<L> for TestScaled {
+    type Structure = usize;
+    type AssetSupplement = usize;
+    type ManagerSupplement = ();
+    fn construct(
+        b: Vec<u8>,
+        scale: &Self::AssetSupplement,
+        _: &Self::ManagerSupplement,
+    ) -> Result<Self::Structure, BoxError> {
+        Ok(b.len() * scale)
+    }
+}
+
+#[test]
+fn test_insert_raw_supplement() {
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("assets/TestAsset.ron");
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestScaled>(());
+    let _loader = builder.finish_loader(());
+
+    let id = manager.insert(&path, 1);
+    assert_eq!(manager.insert_raw(&path, 0, 2), id);
+    assert_eq!(manager.data_asset(id), Some(&2)); //the new supplement replaced the old one
+    manager.unload(id);
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    let len = std::fs::metadata(&path).unwrap().len() as usize;
+    assert_eq!(*manager.get(id).unwrap(), len * 2);
+}
+
+#[test]
+fn test_source() {
+    let dir = std::env::current_dir().unwrap().join("assets");
+    let mut builder = builder::Builder::<ImmediateLoader<RangeSource>>::new();
+    let mut manager = builder.create_manager::<TestBytes>(());
+    let source = RangeSource {
+        root: dir.clone(),
+        loads: Arc::default(),
+    };
+    let _loader = builder.finish_loader_with_source(source, ());
+
+    let id = manager.insert("TestAsset.ron", ());
+    manager.load(id, Some(0..10)).unwrap();
+    manager.maintain();
+    assert_eq!(&*manager.get(id).unwrap(), b"TestStruct");
+    manager.unload(id);
+    manager.load(id, None).unwrap();
+    manager.maintain();
+    assert_eq!(*manager.get(id).unwrap(), std::fs::read(dir.join("TestAsset.ron")).unwrap());
+    manager.load(id, Some(0..1000)).unwrap(); //loading again replaces the loaded data, so the Asset fails
+    manager.maintain();
+    assert!(matches!(manager.error(id), Some(Error::SourceFailed(..))));
+    assert!(manager.status(id).eq(&Some(LoadStatus::Failed)));
+    assert!(manager.get(id).is_none());
+
+    let mut builder = builder::Builder::<ImmediateLoader>::new();
+    let mut manager = builder.create_manager::<TestStruct>(());
+    let _loader = builder.finish_loader_with_source(DiskSource::with_root(&dir), ());
+    let id = manager.insert("TestAssetCopy.ron", ());
+    manager.load(id, ()).unwrap();
+    manager.maintain();
+    assert!(manager.get(id).unwrap()._s.eq(&String::from("123412345")));
+}